@@ -49,5 +49,212 @@ fn bench_crc(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, bench_crc);
+fn bench_many(c: &mut Criterion) {
+    let mut group = c.benchmark_group("CRC64 many");
+    let mut rng = thread_rng();
+
+    for &size in &[64, 200, 1024, 4096] {
+        let mut buf = vec![0u8; size * 64];
+        rng.fill_bytes(&mut buf);
+        let messages: Vec<&[u8]> = buf.chunks(size).collect();
+
+        group.throughput(Throughput::Bytes(buf.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("crc64fast::Digest", size),
+            &messages,
+            |b, messages| {
+                b.iter(|| {
+                    messages
+                        .iter()
+                        .map(|m| {
+                            let mut digest = crc64fast::Digest::new();
+                            digest.write(m);
+                            digest.sum64()
+                        })
+                        .collect::<Vec<_>>()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("crc64fast::checksum_many", size),
+            &messages,
+            |b, messages| b.iter(|| crc64fast::checksum_many(messages)),
+        );
+    }
+}
+
+fn bench_multi(c: &mut Criterion) {
+    let mut group = c.benchmark_group("CRC64 multi");
+    let mut rng = thread_rng();
+
+    for &size in &[8, 16, 26] {
+        let mut buf = vec![0u8; 1 << size];
+        rng.fill_bytes(&mut buf);
+
+        group.throughput(Throughput::Bytes(1 << size));
+        group.bench_with_input(BenchmarkId::new("Digest twice", size), &buf, |b, buf| {
+            b.iter(|| {
+                let mut first = crc64fast::Digest::new();
+                first.write(buf);
+                let mut second = crc64fast::Digest::new();
+                second.write(buf);
+                [first.sum64(), second.sum64()]
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("crc64fast::MultiDigest", size),
+            &buf,
+            |b, buf| {
+                b.iter(|| {
+                    let mut digest = crc64fast::MultiDigest::new([
+                        crc64fast::Variant::Xz,
+                        crc64fast::Variant::Nvme,
+                    ]);
+                    digest.write(buf);
+                    digest.sum64()
+                })
+            },
+        );
+    }
+}
+
+fn bench_short(c: &mut Criterion) {
+    let mut group = c.benchmark_group("CRC64 short writes");
+    let mut rng = thread_rng();
+
+    for &size in &[4, 8, 16] {
+        let mut buf = vec![0u8; 4096];
+        rng.fill_bytes(&mut buf);
+
+        group.throughput(Throughput::Bytes(4096));
+        group.bench_with_input(BenchmarkId::new("Dynamic", size), &buf, |b, buf| {
+            b.iter(|| {
+                let mut digest = crc64fast::Digest::new();
+                for chunk in buf.chunks(size) {
+                    digest.write(chunk);
+                }
+                digest.sum64()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("Auto", size), &buf, |b, buf| {
+            b.iter(|| {
+                let mut digest = crc64fast::Digest::from_backend(crc64fast::backend::Auto::new());
+                for chunk in buf.chunks(size) {
+                    digest.write(chunk);
+                }
+                digest.sum64()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("Table", size), &buf, |b, buf| {
+            b.iter(|| {
+                let mut digest = crc64fast::Digest::from_backend(crc64fast::backend::Table);
+                for chunk in buf.chunks(size) {
+                    digest.write(chunk);
+                }
+                digest.sum64()
+            })
+        });
+    }
+}
+
+fn bench_copy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("CRC64 copy");
+    let mut rng = thread_rng();
+
+    for &size in &[12, 16, 22] {
+        let mut buf = vec![0u8; 1 << size];
+        rng.fill_bytes(&mut buf);
+        let mut page = vec![0u8; 1 << size];
+
+        group.throughput(Throughput::Bytes(1 << size));
+        group.bench_with_input(
+            BenchmarkId::new("copy then Digest", size),
+            &buf,
+            |b, buf| {
+                b.iter(|| {
+                    page.copy_from_slice(buf);
+                    let mut digest = crc64fast::Digest::new();
+                    digest.write(&page);
+                    digest.sum64()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("copy_and_checksum", size),
+            &buf,
+            |b, buf| b.iter(|| crc64fast::copy_and_checksum(&mut page, buf, 0)),
+        );
+    }
+}
+
+fn bench_chunks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("CRC64 chunks");
+    let mut rng = thread_rng();
+
+    for &size in &[64, 1500, 65536] {
+        let mut buf = vec![0u8; 1 << 20];
+        rng.fill_bytes(&mut buf);
+        let chunks: Vec<&[u8]> = buf.chunks(size).collect();
+
+        group.throughput(Throughput::Bytes(buf.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("Digest::write", size),
+            &chunks,
+            |b, chunks| {
+                b.iter(|| {
+                    let mut digest = crc64fast::Digest::new();
+                    for chunk in chunks {
+                        digest.write(chunk);
+                    }
+                    digest.sum64()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("checksum_chunks", size),
+            &chunks,
+            |b, chunks| b.iter(|| crc64fast::checksum_chunks(chunks.iter().copied())),
+        );
+    }
+}
+
+fn bench_blocks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("CRC64 blocks");
+    let mut rng = thread_rng();
+    let mut buf = vec![0u8; 64 << 20];
+    rng.fill_bytes(&mut buf);
+
+    group.throughput(Throughput::Bytes(buf.len() as u64));
+    for &size in &[512, 520, 4096] {
+        let expected = crc64fast::block_checksums(&buf, size);
+        group.bench_with_input(BenchmarkId::new("Digest loop", size), &buf, |b, buf| {
+            b.iter(|| {
+                buf.chunks(size)
+                    .map(|block| {
+                        let mut digest = crc64fast::Digest::new();
+                        digest.write(block);
+                        digest.sum64()
+                    })
+                    .collect::<Vec<_>>()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("block_checksums", size), &buf, |b, buf| {
+            b.iter(|| crc64fast::block_checksums(buf, size))
+        });
+        group.bench_with_input(BenchmarkId::new("verify_blocks", size), &buf, |b, buf| {
+            b.iter(|| crc64fast::verify_blocks(buf, size, &expected))
+        });
+    }
+}
+
+criterion_group!(
+    benches,
+    bench_crc,
+    bench_many,
+    bench_multi,
+    bench_short,
+    bench_copy,
+    bench_chunks,
+    bench_blocks
+);
 criterion_main!(benches);
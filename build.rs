@@ -0,0 +1,35 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+//! Enables the SIMD backends whose inline assembly is stable only in
+//! compilers newer than the minimum supported Rust version.
+
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=RUSTC");
+    println!("cargo:rustc-check-cfg=cfg(crc64fast_powerpc_asm)");
+    println!("cargo:rustc-check-cfg=cfg(crc64fast_s390x_asm)");
+
+    let minor = rustc_minor_version().unwrap_or(0);
+    // `asm!` was stabilized on s390x in Rust 1.84, and on PowerPC in 1.95.
+    if minor >= 84 {
+        println!("cargo:rustc-cfg=crc64fast_s390x_asm");
+    }
+    if minor >= 95 {
+        println!("cargo:rustc-cfg=crc64fast_powerpc_asm");
+    }
+}
+
+/// Returns the minor version of the compiler, e.g. 95 for `rustc 1.95.0`.
+fn rustc_minor_version() -> Option<u32> {
+    let rustc = env::var_os("RUSTC")?;
+    let output = Command::new(rustc).arg("--version").output().ok()?;
+    let version = String::from_utf8(output.stdout).ok()?;
+    let mut parts = version.split_whitespace().nth(1)?.split('.');
+    if parts.next()? != "1" {
+        return None;
+    }
+    parts.next()?.parse().ok()
+}
@@ -0,0 +1,516 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+//! Polynomial quality analysis.
+//!
+//! A CRC with generator P fails to detect an error pattern E exactly when P
+//! divides E. The *Hamming distance* (HD) guaranteed at a data-word length
+//! is the smallest number of flipped bits which can go undetected, and it
+//! shrinks as messages get longer. This module finds, for each HD, the
+//! longest data word still protected by it (the "breakpoints" tabulated by
+//! Koopman).
+//!
+//! Undetected patterns are searched up to 8 bits, so the best guarantee this
+//! module can report is HD = 9. Patterns of up to 4 bits are searched in
+//! O(n²) time and O(n) memory for codewords of n bits, at every requested
+//! length. Heavier ones meet in the middle: the XOR of the syndromes of some
+//! of their bits is looked up in a table of the XORs of every pair or triple
+//! of the others. This takes O(n²) to O(n⁴) time and O(n²) to O(n³) memory,
+//! so they are only searched as far as a fixed budget allows, and the
+//! lengths past it are reported as lower bounds.
+//!
+//! Good 64-bit polynomials keep HD = 9 for a few hundred data bits, past
+//! that budget, so their heavier breakpoints are lower bounds. A w-bit
+//! polynomial P can be analysed as x⁶⁴⁻ʷ·P, which misses the same errors,
+//! e.g. `0x04c1_1db7 << 32` for CRC-32: for w up to about 32, every
+//! breakpoint is exact.
+
+use super::gf2::Poly64;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{BuildHasherDefault, Hasher};
+use std::str::FromStr;
+
+/// The heaviest undetected error pattern searched for.
+const MAX_WEIGHT: usize = 8;
+
+/// The most entries in the syndrome table of one weight.
+const MAX_TABLE_LEN: u128 = 1 << 20;
+
+/// The most lookups into the syndrome table of one weight.
+const MAX_LOOKUPS: u128 = 1 << 26;
+
+/// The notation a polynomial is written in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Notation {
+    /// Most significant bit is the x⁶³ coefficient, x⁶⁴ is implied.
+    /// The ECMA polynomial is `0x42f0_e1eb_a9ea_3693`.
+    Normal,
+    /// Bit-reflected normal notation. The ECMA polynomial is
+    /// `0xc96c_5795_d787_0f42`.
+    Reversed,
+    /// Most significant bit is the x⁶⁴ coefficient, the +1 term is implied.
+    /// The ECMA polynomial is `0xa178_70f5_d4f5_1b49`.
+    Koopman,
+}
+
+impl Notation {
+    /// Interprets `value` as a polynomial written in this notation.
+    pub fn to_poly(self, value: u64) -> Poly64 {
+        match self {
+            Self::Normal => Poly64::new(value),
+            Self::Reversed => Poly64::from_reflected(value),
+            Self::Koopman => Poly64::new(value << 1 | 1),
+        }
+    }
+
+    /// Writes `poly` in this notation.
+    ///
+    /// Polynomials without a +1 term cannot be written in Koopman notation,
+    /// and their +1 term will appear set.
+    pub fn from_poly(self, poly: &Poly64) -> u64 {
+        match self {
+            Self::Normal => poly.poly(),
+            Self::Reversed => poly.reflected(),
+            Self::Koopman => poly.poly() >> 1 | 1 << 63,
+        }
+    }
+}
+
+/// Error returned when parsing an unknown [`Notation`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseNotationError;
+
+impl fmt::Display for ParseNotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("expected one of `normal`, `reversed` or `koopman`")
+    }
+}
+
+impl std::error::Error for ParseNotationError {}
+
+impl FromStr for Notation {
+    type Err = ParseNotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(Self::Normal),
+            "reversed" => Ok(Self::Reversed),
+            "koopman" => Ok(Self::Koopman),
+            _ => Err(ParseNotationError),
+        }
+    }
+}
+
+/// The data-word lengths protected by a given Hamming distance.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HdLimit {
+    /// The Hamming distance: every error of fewer than `hd` bits is detected.
+    pub hd: u32,
+    /// The longest data word, in bits, for which `hd` is known to hold.
+    pub max_data_bits: usize,
+    /// Whether `hd` is lost one bit further. Otherwise, the search stopped
+    /// at `max_data_bits`, either at the requested length or at its budget.
+    pub exact: bool,
+}
+
+/// Computes the Hamming distance breakpoints of `poly` for data words of up
+/// to `max_data_bits` bits.
+///
+/// Returns one entry for each HD from 3 to 9, with non-increasing lengths.
+/// An HD of 2 is guaranteed at every length by any polynomial with at least
+/// two terms.
+pub fn hamming_profile(poly: &Poly64, max_data_bits: usize) -> Vec<HdLimit> {
+    let bounds = search(poly, max_data_bits + 64);
+    (3..=MAX_WEIGHT + 1)
+        .map(|hd| {
+            // The HD is lost in the shortest codeword with a lighter
+            // undetected pattern, or unknown past the shortest search.
+            let bound = bounds[..hd - 2]
+                .iter()
+                .min_by_key(|bound| (bound.len, !bound.found))
+                .unwrap();
+            // A codeword of `len` bits carries `len - 64` data bits, so the
+            // longest protected data word is one bit shorter than that.
+            HdLimit {
+                hd: hd as u32,
+                max_data_bits: bound.len - 65,
+                exact: bound.found,
+            }
+        })
+        .collect()
+}
+
+/// What the search found out about the undetected error patterns of one
+/// weight.
+#[derive(Copy, Clone, Debug)]
+struct Bound {
+    /// There is none in codewords shorter than `len` bits.
+    len: usize,
+    /// Whether there is one in codewords of `len` bits.
+    found: bool,
+}
+
+/// Finds the shortest codeword length (in bits) containing an undetected
+/// error pattern of each weight from 2 to `MAX_WEIGHT`, searching codewords
+/// of up to `max_len` bits.
+///
+/// Once a pattern is found, heavier ones are only searched in shorter
+/// codewords, since the lighter pattern already bounds the HD there.
+fn search(poly: &Poly64, max_len: usize) -> [Bound; MAX_WEIGHT - 1] {
+    // Write P = xᵏ·Q with Q(0) = 1. An error pattern goes undetected when
+    // it is a multiple of both xᵏ and Q, i.e. when its lowest term is at
+    // least xᵏ and Q divides it. Q being coprime with x, shifting a pattern
+    // does not change whether Q divides it, so we only consider patterns
+    // whose lowest term is xᵏ and look for the smallest highest term xᵏ⁺ᵈ.
+    // Polynomials with a +1 term have k = 0.
+    let k = poly.poly().trailing_zeros() as usize;
+    let max_len = max_len.saturating_sub(k);
+    // When Q has an even number of terms, x + 1 divides it, and so every
+    // undetected pattern has an even number of bits.
+    let even = poly.poly().count_ones() % 2 == 1;
+    let never = |weight: usize| even && weight % 2 == 1;
+
+    // Patterns of weight w are searched for d < ends[w - 2].
+    let mut ends = [0; MAX_WEIGHT - 1];
+    for (weight, end) in (2..).zip(&mut ends) {
+        if !never(weight) {
+            *end = search_len(weight, max_len);
+        }
+    }
+    let mut bounds = [Bound {
+        len: usize::MAX,
+        found: false,
+    }; MAX_WEIGHT - 1];
+
+    // syndromes[i] = xᵏ⁺ⁱ mod P. A pattern xᵏ·E goes undetected when the
+    // XOR of its syndromes is zero.
+    let mut syndromes = vec![poly.pow_mod(k as u64)];
+    let mut tables: [Table; 3] = Default::default();
+    let mut s = poly.mul_x(syndromes[0]);
+    for d in 1.. {
+        if ends.iter().all(|end| d >= *end) {
+            break;
+        }
+        let target = syndromes[0] ^ s;
+        for weight in 2..=MAX_WEIGHT {
+            if d >= ends[weight - 2] || !undetected(weight, target, &syndromes, &tables) {
+                continue;
+            }
+            bounds[weight - 2] = Bound {
+                len: k + d + 1,
+                found: true,
+            };
+            for end in &mut ends[weight - 2..] {
+                *end = (*end).min(d);
+            }
+            break;
+        }
+
+        // weight 2 only needs the syndrome of bit 0.
+        if ends[1..].iter().any(|end| d + 1 < *end) {
+            syndromes.push(s);
+            for (r, table) in (1..).zip(&mut tables) {
+                // a table is only filled while a weight using it is
+                // searched, and dropped for good afterwards.
+                let used = (2..)
+                    .zip(&ends)
+                    .any(|(weight, end)| split(weight).0 == r && d + 1 < *end);
+                if used {
+                    table.extend(&syndromes, r);
+                } else if !table.0.is_empty() {
+                    *table = Table::default();
+                }
+            }
+        }
+        s = poly.mul_x(s);
+    }
+
+    for ((weight, bound), end) in (2..).zip(&mut bounds).zip(ends) {
+        if !bound.found && !never(weight) {
+            // codewords of up to k + end bits have been searched.
+            bound.len = k + end + 1;
+        }
+    }
+    bounds
+}
+
+/// How the bits of a pattern of `weight` bits, besides its lowest and
+/// highest ones, are split between the syndrome table and the lookups into
+/// it: the table holds every XOR of `r` syndromes, and each length looks up
+/// the XOR of every `q` syndromes.
+fn split(weight: usize) -> (usize, usize) {
+    let free = weight - 2;
+    (free - free / 2, free / 2)
+}
+
+/// Returns the longest codeword, without its k lowest bits, in which
+/// patterns of `weight` bits are searched.
+///
+/// Weights of up to 4 only need a table of single syndromes and are
+/// searched in codewords of up to `max_len` bits. Heavier ones stop where
+/// their table or lookups would exceed the budget.
+fn search_len(weight: usize, max_len: usize) -> usize {
+    let (r, q) = split(weight);
+    if r <= 1 {
+        return max_len;
+    }
+    let fits =
+        |len: usize| binomial(len, r) <= MAX_TABLE_LEN && binomial(len, q + 1) <= MAX_LOOKUPS;
+    let (mut lo, mut hi) = (0, max_len);
+    while lo < hi {
+        let mid = hi - (hi - lo) / 2;
+        if fits(mid) {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    lo
+}
+
+/// Computes the number of ways to choose `k` items among `n`, saturating.
+fn binomial(n: usize, k: usize) -> u128 {
+    (0..k).fold(1, |acc: u128, i| {
+        acc.saturating_mul(n.saturating_sub(i) as u128) / (i as u128 + 1)
+    })
+}
+
+/// Whether a pattern of `weight` bits made of bit 0, bit d and bits in
+/// between goes undetected, `target` being the XOR of the syndromes of
+/// bits 0 and d, and `syndromes` those of the bits below d.
+fn undetected(weight: usize, target: u64, syndromes: &[u64], tables: &[Table; 3]) -> bool {
+    let (r, q) = split(weight);
+    if r == 0 {
+        return target == 0;
+    }
+    // a table entry sharing a bit with the lookup makes a lighter pattern.
+    any_subset(
+        syndromes,
+        1,
+        q,
+        &mut [0; 3],
+        0,
+        target,
+        &mut |xor, bits| matches!(tables[r - 1].0.get(&xor), Some(found) if !found[..r].iter().any(|b| bits.contains(b))),
+    )
+}
+
+/// Calls `f` with `xor` XORed with the syndromes of `n` more bits, from
+/// `start` upwards, and the bits chosen so far, until it returns `true`.
+fn any_subset(
+    syndromes: &[u64],
+    start: usize,
+    n: usize,
+    bits: &mut [u32; 3],
+    chosen: usize,
+    xor: u64,
+    f: &mut impl FnMut(u64, &[u32]) -> bool,
+) -> bool {
+    if n == 0 {
+        return f(xor, &bits[..chosen]);
+    }
+    (start..syndromes.len()).any(|i| {
+        bits[chosen] = i as u32;
+        any_subset(
+            syndromes,
+            i + 1,
+            n - 1,
+            bits,
+            chosen + 1,
+            xor ^ syndromes[i],
+            f,
+        )
+    })
+}
+
+/// The XOR of the syndromes of every `r` bits among bits 1 to d, and the
+/// bits it was first found with.
+///
+/// Two sets of bits with the same XOR make a lighter undetected pattern in
+/// a shorter codeword, which ends the search of the weights using the table,
+/// so keeping either one is enough.
+#[derive(Default)]
+struct Table(HashMap<u64, [u32; 3], BuildHasherDefault<SyndromeHasher>>);
+
+impl Table {
+    /// Adds the XORs of `r` bits whose highest one is the last of
+    /// `syndromes`.
+    fn extend(&mut self, syndromes: &[u64], r: usize) {
+        let (&last, lower) = syndromes.split_last().unwrap();
+        any_subset(lower, 1, r - 1, &mut [0; 3], 0, last, &mut |xor, bits| {
+            let mut found = [0; 3];
+            found[..r - 1].copy_from_slice(bits);
+            found[r - 1] = lower.len() as u32;
+            self.0.entry(xor).or_insert(found);
+            false
+        });
+    }
+}
+
+/// Hashes syndromes, which are already close to uniformly distributed, with
+/// a single multiplication.
+#[derive(Default)]
+struct SyndromeHasher(u64);
+
+impl Hasher for SyndromeHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, _: &[u8]) {
+        unreachable!("only syndromes are hashed")
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        let product = value.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        self.0 = product ^ product >> 32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gf2::poly_mul;
+
+    #[test]
+    fn test_notation() {
+        for notation in [Notation::Normal, Notation::Reversed, Notation::Koopman] {
+            let value = notation.from_poly(&Poly64::ECMA);
+            assert_eq!(notation.to_poly(value), Poly64::ECMA, "{:?}", notation);
+        }
+        assert_eq!(
+            Notation::Reversed.from_poly(&Poly64::ECMA),
+            0xc96c_5795_d787_0f42
+        );
+        assert_eq!(
+            Notation::Koopman.from_poly(&Poly64::ECMA),
+            0xa178_70f5_d4f5_1b49
+        );
+    }
+
+    #[test]
+    fn test_hamming_profile() {
+        // x⁶⁴ + x⁴ + x³ + x + 1 is primitive, so x has order 2⁶⁴ - 1 and
+        // two-bit errors are always detected.
+        let profile = hamming_profile(&Poly64::new(0x1b), 256);
+        assert_eq!(
+            profile[0],
+            HdLimit {
+                hd: 3,
+                max_data_bits: 256,
+                exact: false
+            }
+        );
+
+        // x⁶⁴ + 1 misses two-bit errors 64 bits apart.
+        let profile = hamming_profile(&Poly64::new(1), 256);
+        let expected: Vec<_> = (3..=9)
+            .map(|hd| HdLimit {
+                hd,
+                max_data_bits: 0,
+                exact: true,
+            })
+            .collect();
+        assert_eq!(profile, expected);
+    }
+
+    #[test]
+    fn test_koopman() {
+        // x³²·P misses exactly the errors P misses, shifted by 32 bits, so it
+        // has the data-word lengths of P, published in Koopman's "32-Bit
+        // Cyclic Redundancy Codes for Internet Applications".
+        let crc32 = Poly64::new(0x04c1_1db7 << 32);
+        let profile: Vec<_> = hamming_profile(&crc32, 3000)
+            .iter()
+            .map(|limit| (limit.hd, limit.max_data_bits, limit.exact))
+            .collect();
+        assert_eq!(
+            profile,
+            [
+                (3, 3000, false),
+                (4, 3000, false),
+                (5, 2974, true),
+                (6, 268, true),
+                (7, 171, true),
+                (8, 91, true),
+                (9, 57, true),
+            ]
+        );
+
+        // CRC-32C is divisible by x + 1, so its HD is always even.
+        let crc32c = Poly64::new(0x1edc_6f41 << 32);
+        let profile: Vec<_> = hamming_profile(&crc32c, 5300)
+            .iter()
+            .map(|limit| (limit.hd, limit.max_data_bits, limit.exact))
+            .collect();
+        assert_eq!(
+            profile,
+            [
+                (3, 5300, false),
+                (4, 5300, false),
+                (5, 5243, true),
+                (6, 5243, true),
+                (7, 177, true),
+                (8, 177, true),
+                (9, 47, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_brute_force() {
+        for poly in [
+            1,
+            1 << 32 | 1,
+            0x1b,
+            0x8000_0000_0000_0003,
+            0x0000_0001_0000_0003,
+            0x42f0_e1eb_a9ea_3693,
+            // without a +1 term.
+            0x2,
+            0x8000_0000_0000_0006,
+            0x42f0_e1eb_a9ea_3690,
+            // 8- and 16-bit CRCs, shifted up to 64 bits.
+            0x07 << 56,
+            0x2f << 56,
+            0x9b << 56,
+            0x06 << 56,
+            0x1021 << 48,
+            0x8005 << 48,
+        ] {
+            let poly = Poly64::new(poly);
+            assert_eq!(
+                hamming_profile(&poly, 16),
+                brute_force_profile(&poly, 16),
+                "{:?}",
+                poly
+            );
+        }
+    }
+
+    /// Computes the same result as `hamming_profile` by enumerating every
+    /// codeword, i.e. every multiple of the polynomial.
+    fn brute_force_profile(poly: &Poly64, max_data_bits: usize) -> Vec<HdLimit> {
+        // min_weights[n] is the lightest codeword with up to n data bits.
+        let mut min_weights = vec![u32::MAX; max_data_bits + 1];
+        for m in 1..1u64 << max_data_bits {
+            let codeword = u128::from(m) << 64 ^ poly_mul(m, poly.poly());
+            let data_bits = 64 - m.leading_zeros() as usize;
+            let weight = &mut min_weights[data_bits];
+            *weight = (*weight).min(codeword.count_ones());
+        }
+        for n in 1..=max_data_bits {
+            min_weights[n] = min_weights[n].min(min_weights[n - 1]);
+        }
+        (3..=9)
+            .map(|hd| {
+                let bits = min_weights.iter().take_while(|w| **w >= hd).count() - 1;
+                HdLimit {
+                    hd,
+                    max_data_bits: bits,
+                    exact: bits < max_data_bits,
+                }
+            })
+            .collect()
+    }
+}
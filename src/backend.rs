@@ -0,0 +1,323 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+//! Compile-time selection of the CRC-64 implementation.
+//!
+//! [`Digest`](crate::Digest) is generic over an [`Engine`], given to
+//! [`Digest::from_backend`](crate::Digest::from_backend). The default,
+//! [`Dynamic`], picks the implementation at runtime and calls it through a
+//! function pointer. The other backends are called directly, so that short
+//! writes can be inlined into the caller.
+//!
+//! ```
+//! use crc64fast::backend::{Auto, Table};
+//! use crc64fast::Digest;
+//!
+//! let mut c = Digest::from_backend(Auto::new());
+//! c.write(b"123456789");
+//! assert_eq!(c.sum64(), 0x995d_c9bb_df19_39fa);
+//!
+//! let mut c = Digest::from_backend(Table);
+//! c.write(b"123456789");
+//! assert_eq!(c.sum64(), 0x995d_c9bb_df19_39fa);
+//! ```
+
+use super::{pclmulqdq, table, UpdateChunksFn, UpdateFn};
+use std::fmt;
+use std::sync::Arc;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// An implementation of CRC-64-ECMA, used by [`Digest`](crate::Digest).
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait Engine: Clone + private::Sealed {
+    /// Updates the CRC `state` with `bytes`.
+    ///
+    /// The state is the bitwise complement of the CRC value, as kept by
+    /// [`Digest`](crate::Digest).
+    fn update(&self, state: u64, bytes: &[u8]) -> u64;
+
+    /// Updates the CRC `state` with the concatenation of `chunks`.
+    fn update_chunks(&self, state: u64, chunks: &mut dyn Iterator<Item = &[u8]>) -> u64 {
+        chunks.fold(state, |state, bytes| self.update(state, bytes))
+    }
+}
+
+/// The table-based implementation, available everywhere.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Table;
+
+impl private::Sealed for Table {}
+
+impl Engine for Table {
+    #[inline]
+    fn update(&self, state: u64, bytes: &[u8]) -> u64 {
+        table::update(state, bytes)
+    }
+}
+
+/// The SIMD carryless multiplication implementation, on every platform
+/// supported by the crate.
+#[derive(Copy, Clone, Debug)]
+pub struct Simd(());
+
+impl Simd {
+    /// Returns the backend, or `None` if the CPU does not support it.
+    pub fn new() -> Option<Self> {
+        pclmulqdq::is_supported().then_some(Self(()))
+    }
+}
+
+impl private::Sealed for Simd {}
+
+impl Engine for Simd {
+    #[inline]
+    fn update(&self, state: u64, bytes: &[u8]) -> u64 {
+        pclmulqdq::update_simd_only(state, bytes)
+    }
+
+    fn update_chunks(&self, state: u64, chunks: &mut dyn Iterator<Item = &[u8]>) -> u64 {
+        pclmulqdq::update_chunks_simd_only(state, chunks)
+    }
+}
+
+/// The SIMD implementation if the crate is built with the target features it
+/// needs (e.g. `-C target-feature=+pclmulqdq,+sse4.1` on x86, or
+/// `-C target-feature=+aes` on AArch64), without any runtime detection.
+///
+/// Otherwise, the implementation is detected at runtime on the first write
+/// of the process, and reused by all the later ones, like [`Dynamic`] does
+/// for each digest.
+///
+/// In both cases, `CRC64FAST_BACKEND=table` selects the table-based
+/// implementation. The variable is resolved when the backend is created, so
+/// writes do not check it again.
+#[derive(Copy, Clone, Debug)]
+pub struct Auto {
+    static_simd: bool,
+}
+
+impl Auto {
+    /// Creates the backend, reading `CRC64FAST_BACKEND` if it was not read
+    /// yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the variable is set to an invalid value.
+    pub fn new() -> Self {
+        Self {
+            static_simd: pclmulqdq::use_static_simd(),
+        }
+    }
+}
+
+impl Default for Auto {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl private::Sealed for Auto {}
+
+impl Engine for Auto {
+    #[inline]
+    fn update(&self, state: u64, bytes: &[u8]) -> u64 {
+        pclmulqdq::update_auto(self.static_simd, state, bytes)
+    }
+
+    fn update_chunks(&self, state: u64, chunks: &mut dyn Iterator<Item = &[u8]>) -> u64 {
+        pclmulqdq::update_chunks_auto(self.static_simd, state, chunks)
+    }
+}
+
+/// The fastest implementation detected at runtime, called through a
+/// function pointer.
+#[derive(Copy, Clone, Debug)]
+pub struct Dynamic {
+    computer: UpdateFn,
+    chunks: UpdateChunksFn,
+}
+
+impl Dynamic {
+    pub(crate) fn with_computer(computer: UpdateFn, chunks: UpdateChunksFn) -> Self {
+        Self { computer, chunks }
+    }
+}
+
+impl Default for Dynamic {
+    /// Performs runtime CPU feature detection to determine which
+    /// implementation to choose.
+    fn default() -> Self {
+        Self::with_computer(pclmulqdq::get_update(), pclmulqdq::get_update_chunks())
+    }
+}
+
+impl private::Sealed for Dynamic {}
+
+impl Engine for Dynamic {
+    #[inline]
+    fn update(&self, state: u64, bytes: &[u8]) -> u64 {
+        (self.computer)(state, bytes)
+    }
+
+    fn update_chunks(&self, state: u64, chunks: &mut dyn Iterator<Item = &[u8]>) -> u64 {
+        (self.chunks)(state, chunks)
+    }
+}
+
+/// A callback invoked by [`Verified`] when the implementations disagree.
+type DivergenceFn = Arc<dyn Fn(&Divergence<'_>) + Send + Sync>;
+
+/// The implementation detected at runtime, shadowed by the table-based one.
+///
+/// Every write is computed twice, and the results compared. This halves the
+/// throughput at best, and is meant to verify a sample of the data against
+/// silent hardware faults. Created by
+/// [`Digest::new_verified`](crate::Digest::new_verified).
+#[derive(Clone)]
+pub struct Verified {
+    computer: UpdateFn,
+    backend: crate::Backend,
+    on_divergence: Option<DivergenceFn>,
+}
+
+impl Verified {
+    pub(crate) fn new(on_divergence: Option<DivergenceFn>) -> Self {
+        Self::with_computer(
+            pclmulqdq::get_update(),
+            pclmulqdq::detected_backend(),
+            on_divergence,
+        )
+    }
+
+    fn with_computer(
+        computer: UpdateFn,
+        backend: crate::Backend,
+        on_divergence: Option<DivergenceFn>,
+    ) -> Self {
+        Self {
+            computer,
+            backend,
+            on_divergence,
+        }
+    }
+}
+
+impl fmt::Debug for Verified {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Verified")
+            .field("backend", &self.backend)
+            .finish_non_exhaustive()
+    }
+}
+
+impl private::Sealed for Verified {}
+
+impl Engine for Verified {
+    /// Returns the state computed by the table-based implementation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the states differ and no callback was given.
+    fn update(&self, state: u64, bytes: &[u8]) -> u64 {
+        let actual = (self.computer)(state, bytes);
+        let expected = table::update(state, bytes);
+        if actual != expected {
+            let divergence = Divergence {
+                backend: self.backend,
+                input: bytes,
+                state,
+                expected,
+                actual,
+            };
+            match &self.on_divergence {
+                Some(on_divergence) => on_divergence(&divergence),
+                None => panic!("{}", divergence),
+            }
+        }
+        expected
+    }
+}
+
+/// A write for which the detected implementation and the table-based one
+/// disagree, as reported by [`Verified`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Divergence<'a> {
+    /// The detected implementation.
+    pub backend: crate::Backend,
+    /// The data written.
+    pub input: &'a [u8],
+    /// The CRC state before the write.
+    pub state: u64,
+    /// The state computed by the table-based implementation.
+    pub expected: u64,
+    /// The state computed by `backend`.
+    pub actual: u64,
+}
+
+impl fmt::Display for Divergence<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} backend computed state {:#018x} instead of {:#018x} for {} bytes from state {:#018x}",
+            self.backend,
+            self.actual,
+            self.expected,
+            self.input.len(),
+            self.state
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Digest;
+    use std::sync::Mutex;
+
+    fn broken(state: u64, bytes: &[u8]) -> u64 {
+        let state = table::update(state, bytes);
+        if bytes.len() == 3 {
+            !state
+        } else {
+            state
+        }
+    }
+
+    #[test]
+    fn test_verified() {
+        let mut digest = Digest::new_verified();
+        digest.write(b"12345");
+        digest.write(b"6789");
+        assert_eq!(digest.sum64(), 0x995d_c9bb_df19_39fa);
+    }
+
+    #[test]
+    fn test_divergence_callback() {
+        let seen = Arc::new(Mutex::new(vec![]));
+        let on_divergence: DivergenceFn = {
+            let seen = seen.clone();
+            Arc::new(move |d: &Divergence<'_>| {
+                seen.lock().unwrap().push((d.input.to_vec(), d.state))
+            })
+        };
+        let backend =
+            Verified::with_computer(broken, crate::Backend::FakeSimd, Some(on_divergence));
+        let mut digest = Digest::from_backend(backend);
+        digest.write(b"123456");
+        digest.write(b"789");
+        // the state computed by the table is kept.
+        assert_eq!(digest.sum64(), 0x995d_c9bb_df19_39fa);
+        let state = table::update(!0, b"123456");
+        assert_eq!(*seen.lock().unwrap(), [(b"789".to_vec(), state)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "fake-simd backend computed state")]
+    fn test_divergence_panic() {
+        let backend = Verified::with_computer(broken, crate::Backend::FakeSimd, None);
+        Digest::from_backend(backend).write(b"abc");
+    }
+}
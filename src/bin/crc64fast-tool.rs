@@ -0,0 +1,106 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+// usage:
+//
+//  crc64fast-tool hd <poly> [normal|reversed|koopman] [max-data-bits]
+//  crc64fast-tool reveng <message-hex>:<crc-hex>...
+//
+// e.g.
+//
+//  crc64fast-tool hd 0x42f0e1eba9ea3693
+//  crc64fast-tool hd 0xad93d23594c93659 normal 4096
+//  crc64fast-tool reveng 3132:0x041d6d7e27f25958 3133:0xb73321c024550637 ...
+
+use crc64fast::analysis::{hamming_profile, Notation};
+use crc64fast::reveng::reveng;
+use std::env::args;
+use std::process::exit;
+
+const USAGE: &str = "usage:
+    crc64fast-tool hd <poly> [normal|reversed|koopman] [max-data-bits]
+    crc64fast-tool reveng <message-hex>:<crc-hex>...";
+
+fn parse_hex(s: &str) -> Result<u64, String> {
+    let digits = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+    let digits = digits.replace('_', "");
+    u64::from_str_radix(&digits, 16).map_err(|e| format!("invalid hex number `{}`: {}", s, e))
+}
+
+fn parse_bytes(s: &str) -> Result<Vec<u8>, String> {
+    // slicing two bytes at a time below needs every char to be one byte.
+    if let Some(c) = s.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(format!("invalid hex bytes `{}`: invalid digit `{}`", s, c));
+    }
+    if s.len() % 2 != 0 {
+        return Err(format!("odd number of hex digits in `{}`", s));
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&s[i..i + 2], 16)
+                .map_err(|e| format!("invalid hex bytes `{}`: {}", s, e))
+        })
+        .collect()
+}
+
+fn hd(args: &[String]) -> Result<(), String> {
+    let poly = parse_hex(args.first().ok_or(USAGE)?)?;
+    let notation = match args.get(1) {
+        Some(n) => n.parse::<Notation>().map_err(|e| e.to_string())?,
+        None => Notation::Normal,
+    };
+    let max_data_bits = match args.get(2) {
+        Some(n) => n
+            .parse()
+            .map_err(|e| format!("invalid length `{}`: {}", n, e))?,
+        None => 2048,
+    };
+
+    let poly = notation.to_poly(poly);
+    println!("normal:   {:#018x}", Notation::Normal.from_poly(&poly));
+    println!("reversed: {:#018x}", Notation::Reversed.from_poly(&poly));
+    println!("koopman:  {:#018x}", Notation::Koopman.from_poly(&poly));
+    println!();
+    for limit in hamming_profile(&poly, max_data_bits) {
+        if limit.exact {
+            println!("HD={}  up to {} data bits", limit.hd, limit.max_data_bits);
+        } else {
+            println!(
+                "HD={}  up to at least {} data bits",
+                limit.hd, limit.max_data_bits
+            );
+        }
+    }
+    Ok(())
+}
+
+fn reveng_command(args: &[String]) -> Result<(), String> {
+    let samples = args
+        .iter()
+        .map(|arg| {
+            let (msg, crc) = arg.split_once(':').ok_or(USAGE)?;
+            Ok((parse_bytes(msg)?, parse_hex(crc)?))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let samples: Vec<(&[u8], u64)> = samples.iter().map(|(m, c)| (&**m, *c)).collect();
+    for params in reveng(&samples).map_err(|e| e.to_string())? {
+        println!("{}", params);
+    }
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = args().skip(1).collect();
+    let result = match args.first().map(|s| s.as_str()) {
+        Some("hd") => hd(&args[1..]),
+        Some("reveng") => reveng_command(&args[1..]),
+        _ => Err(USAGE.to_owned()),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        exit(1);
+    }
+}
@@ -0,0 +1,285 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+//! Combination of the CRC-64 values of adjacent messages.
+//!
+//! The CRC register is linear, so the CRC of `A ‖ B` only depends on the
+//! CRCs of `A` and `B` and the length of `B`: the contribution of `A` is its
+//! CRC followed by `|B|` zero bytes, i.e. multiplied by x^(8·|B|) modulo the
+//! polynomial. The initial value and final XOR of CRC-64-ECMA cancel out, as
+//! both are all ones. [`PositionalDigest`] relies on this to accept the
+//! chunks of an object in any order.
+
+use super::gf2::{reflect, Poly64};
+use super::{pclmulqdq, UpdateFn};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Mutex;
+
+/// Feeds `len` zero bytes through a zero-initialized CRC-64-ECMA register
+/// holding `crc`.
+///
+/// This takes O(log `len`) carryless multiplications.
+pub(crate) fn shift(crc: u64, len: u64) -> u64 {
+    let p = Poly64::ECMA;
+    // x^(8·len), squaring x^len three times so that `len` cannot overflow.
+    let k = (0..3).fold(p.pow_mod(len), |k, _| p.mul_mod(k, k));
+    reflect(p.mul_mod(reflect(crc), k))
+}
+
+/// Computes the CRC-64-ECMA value of `A ‖ B` from the CRC-64-ECMA values
+/// `crc_a` of `A` and `crc_b` of `B`, and the length `len_b` of `B`.
+///
+/// This takes O(log `len_b`) time, without the data.
+pub(crate) fn combine(crc_a: u64, crc_b: u64, len_b: u64) -> u64 {
+    shift(crc_a, len_b) ^ crc_b
+}
+
+/// Computes the CRC-64-ECMA value of an object from chunks written in any
+/// order, possibly from several threads.
+///
+/// The CRC of each chunk is computed on its own, outside of any lock, then
+/// shifted by its distance from the end of the object and accumulated. The
+/// chunks must not overlap, and the value is available once every byte of
+/// the object has been written.
+///
+/// ```
+/// use crc64fast::combine::PositionalDigest;
+///
+/// let digest = PositionalDigest::new(9);
+/// std::thread::scope(|s| {
+///     s.spawn(|| digest.write_at(4, b"56789").unwrap());
+///     s.spawn(|| digest.write_at(0, b"1234").unwrap());
+/// });
+/// assert_eq!(digest.sum64(), Some(0x995d_c9bb_df19_39fa));
+/// ```
+#[derive(Debug)]
+pub struct PositionalDigest {
+    total_len: u64,
+    computer: UpdateFn,
+    inner: Mutex<Written>,
+}
+
+/// The chunks written so far into a [`PositionalDigest`].
+#[derive(Debug, Default)]
+struct Written {
+    /// The XOR of the zero-initialized CRC registers of the chunks, each
+    /// followed by the zero bytes up to the end of the object.
+    state: u64,
+    /// The byte ranges written, as disjoint and non-adjacent `start..end`
+    /// entries keyed by `start`.
+    ranges: BTreeMap<u64, u64>,
+    /// The number of bytes written.
+    len: u64,
+}
+
+impl Written {
+    /// Records the range `start..end`, merging it with the adjacent ones.
+    /// Returns `false` if it overlaps a range already written.
+    fn insert(&mut self, start: u64, end: u64) -> bool {
+        let prev = self
+            .ranges
+            .range(..=start)
+            .next_back()
+            .map(|(s, e)| (*s, *e));
+        let next = self.ranges.range(start..).next().map(|(s, e)| (*s, *e));
+        if prev.is_some_and(|(_, e)| e > start) || next.is_some_and(|(s, _)| s < end) {
+            return false;
+        }
+
+        let (mut start, mut end) = (start, end);
+        if let Some((s, _)) = prev.filter(|(_, e)| *e == start) {
+            self.ranges.remove(&s);
+            start = s;
+        }
+        if let Some((s, e)) = next.filter(|(s, _)| *s == end) {
+            self.ranges.remove(&s);
+            end = e;
+        }
+        self.ranges.insert(start, end);
+        true
+    }
+}
+
+impl PositionalDigest {
+    /// Creates a digest for an object of `total_len` bytes.
+    pub fn new(total_len: u64) -> Self {
+        Self {
+            total_len,
+            computer: pclmulqdq::get_update(),
+            inner: Mutex::new(Written::default()),
+        }
+    }
+
+    /// Returns the length of the object.
+    pub fn total_len(&self) -> u64 {
+        self.total_len
+    }
+
+    /// Writes the chunk of the object starting at `offset`.
+    ///
+    /// Returns an error, without writing anything, if the chunk extends past
+    /// the end of the object or overlaps a chunk already written.
+    pub fn write_at(&self, offset: u64, bytes: &[u8]) -> Result<(), WriteAtError> {
+        let len = bytes.len() as u64;
+        let end = offset
+            .checked_add(len)
+            .filter(|end| *end <= self.total_len)
+            .ok_or(WriteAtError::OutOfBounds { offset, len })?;
+        if len == 0 {
+            return Ok(());
+        }
+
+        let state = shift((self.computer)(0, bytes), self.total_len - end);
+
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if !inner.insert(offset, end) {
+            return Err(WriteAtError::Overlap { offset, len });
+        }
+        inner.state ^= state;
+        inner.len += len;
+        Ok(())
+    }
+
+    /// Returns the number of bytes of the object not written yet.
+    pub fn remaining(&self) -> u64 {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        self.total_len - inner.len
+    }
+
+    /// Computes the CRC-64-ECMA value of the object, or returns `None` if
+    /// some of its bytes have not been written yet.
+    pub fn sum64(&self) -> Option<u64> {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if inner.len < self.total_len {
+            return None;
+        }
+        // add the contribution of the initial value, and the final XOR.
+        Some(!(inner.state ^ shift(!0, self.total_len)))
+    }
+}
+
+/// Error returned by [`PositionalDigest::write_at`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum WriteAtError {
+    /// The chunk extends past the end of the object.
+    OutOfBounds {
+        /// The offset of the chunk.
+        offset: u64,
+        /// The length of the chunk.
+        len: u64,
+    },
+    /// The chunk overlaps a chunk already written.
+    Overlap {
+        /// The offset of the chunk.
+        offset: u64,
+        /// The length of the chunk.
+        len: u64,
+    },
+}
+
+impl fmt::Display for WriteAtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds { offset, len } => write!(
+                f,
+                "chunk of {} bytes at offset {} extends past the end of the object",
+                len, offset
+            ),
+            Self::Overlap { offset, len } => write!(
+                f,
+                "chunk of {} bytes at offset {} overlaps a chunk already written",
+                len, offset
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WriteAtError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table;
+    use crc::{Crc, CRC_64_XZ};
+    use proptest::prelude::*;
+
+    const CRC: Crc<u64> = Crc::<u64>::new(&CRC_64_XZ);
+
+    #[test]
+    fn test_shift() {
+        let state = table::update(0, b"123456789");
+        for len in [0, 1, 7, 8, 100, 4096] {
+            assert_eq!(
+                shift(state, len),
+                table::update(state, &vec![0; len as usize])
+            );
+        }
+    }
+
+    #[test]
+    fn test_write_at_errors() {
+        let digest = PositionalDigest::new(10);
+        assert_eq!(digest.write_at(3, b"abc"), Ok(()));
+        assert_eq!(
+            digest.write_at(8, b"abc"),
+            Err(WriteAtError::OutOfBounds { offset: 8, len: 3 })
+        );
+        assert_eq!(
+            digest.write_at(u64::MAX, b"a"),
+            Err(WriteAtError::OutOfBounds {
+                offset: u64::MAX,
+                len: 1
+            })
+        );
+        assert_eq!(
+            digest.write_at(5, b"abc"),
+            Err(WriteAtError::Overlap { offset: 5, len: 3 })
+        );
+        assert_eq!(
+            digest.write_at(0, b"abcd"),
+            Err(WriteAtError::Overlap { offset: 0, len: 4 })
+        );
+        assert_eq!(digest.write_at(0, b"abc"), Ok(()));
+        assert_eq!(digest.write_at(6, b""), Ok(()));
+        assert_eq!(digest.remaining(), 4);
+        assert_eq!(digest.sum64(), None);
+        assert_eq!(digest.write_at(6, b"abcd"), Ok(()));
+        assert_eq!(digest.remaining(), 0);
+        assert_eq!(digest.sum64(), Some(CRC.checksum(b"abcabcabcd")));
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(PositionalDigest::new(0).sum64(), Some(0));
+    }
+
+    proptest! {
+        #[test]
+        fn combine_matches(a in any::<Vec<u8>>(), b in any::<Vec<u8>>()) {
+            let crc = combine(CRC.checksum(&a), CRC.checksum(&b), b.len() as u64);
+            prop_assert_eq!(crc, CRC.checksum(&[a, b].concat()));
+        }
+
+        #[test]
+        fn out_of_order(
+            bytes in proptest::collection::vec(any::<u8>(), 0..8192),
+            splits in proptest::collection::vec(any::<prop::sample::Index>(), 0..16),
+            order in any::<prop::sample::Index>(),
+        ) {
+            let mut bounds: Vec<usize> = splits.iter().map(|i| i.index(bytes.len() + 1)).collect();
+            bounds.extend([0, bytes.len()]);
+            bounds.sort_unstable();
+            let mut chunks: Vec<(usize, &[u8])> =
+                bounds.windows(2).map(|w| (w[0], &bytes[w[0]..w[1]])).collect();
+            let rotation = order.index(chunks.len());
+            chunks.rotate_left(rotation);
+            chunks.reverse();
+
+            let digest = PositionalDigest::new(bytes.len() as u64);
+            for (offset, chunk) in chunks {
+                prop_assert_eq!(digest.write_at(offset as u64, chunk), Ok(()));
+            }
+            prop_assert_eq!(digest.sum64(), Some(CRC.checksum(&bytes)));
+        }
+    }
+}
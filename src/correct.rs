@@ -0,0 +1,220 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+//! Single- and double-bit error location and correction.
+//!
+//! CRC-64 is linear, so for a corrupted message the *syndrome*
+//! `computed ⊕ expected` depends only on the flipped bits, and equals the
+//! XOR of the syndromes of each flipped bit taken individually. The syndrome
+//! of a single bit depends only on its distance from the end of the message,
+//! which lets us precompute all of them for a given message length and look
+//! the observed syndrome up.
+//!
+//! Bit positions are numbered `byte_index * 8 + bit_index`, where
+//! `bit_index` 0 is the least significant bit of the byte (CRC-64/XZ is a
+//! reflected CRC, so this is the first bit fed into the register).
+//!
+//! Correction is only unambiguous as long as the polynomial guarantees a
+//! Hamming distance of at least 3 (single bit) or 5 (double bit) at the
+//! given message length. If more than one error pattern explains the
+//! syndrome, no correction is proposed.
+
+use super::{table, Digest};
+use std::collections::HashMap;
+
+/// The largest number of flipped bits which can be located.
+const MAX_BITS: u8 = 2;
+
+/// Precomputed error-position syndromes for messages of a fixed length.
+///
+/// Building an `ErrorLocator` takes O(n) time and memory for an `n`-byte
+/// message. When checking many blocks of the same size, reuse it instead of
+/// calling [`locate_errors`] repeatedly.
+#[derive(Clone, Debug)]
+pub struct ErrorLocator {
+    /// `syndromes[i]` is the syndrome of flipping bit `i`.
+    syndromes: Vec<u64>,
+    /// Maps a syndrome back to the bit position producing it, or `None` if
+    /// several bit positions share the same syndrome.
+    positions: HashMap<u64, Option<usize>>,
+}
+
+impl ErrorLocator {
+    /// Precomputes the syndromes for messages of `len` bytes.
+    pub fn new(len: usize) -> Self {
+        let mut syndromes = vec![0; len * 8];
+
+        // The syndrome of a bit in the last byte is the table entry of that
+        // bit. Each byte further from the end multiplies it by x⁸, i.e.
+        // feeds a zero byte through the register.
+        let mut current: [u64; 8] = [0; 8];
+        for (bit, s) in current.iter_mut().enumerate() {
+            *s = table::update(0, &[1 << bit]);
+        }
+        for byte in (0..len).rev() {
+            syndromes[byte * 8..byte * 8 + 8].copy_from_slice(&current);
+            for s in &mut current {
+                *s = table::update(*s, &[0]);
+            }
+        }
+
+        let mut positions = HashMap::with_capacity(syndromes.len());
+        for (i, s) in syndromes.iter().enumerate() {
+            positions
+                .entry(*s)
+                .and_modify(|p| *p = None)
+                .or_insert(Some(i));
+        }
+
+        Self {
+            syndromes,
+            positions,
+        }
+    }
+
+    /// Returns the message length (in bytes) this locator was built for.
+    pub fn len(&self) -> usize {
+        self.syndromes.len() / 8
+    }
+
+    /// Returns whether this locator was built for empty messages.
+    pub fn is_empty(&self) -> bool {
+        self.syndromes.is_empty()
+    }
+
+    /// Finds the bits which must be flipped in `data` to make its CRC-64
+    /// equal `expected`.
+    ///
+    /// Returns an empty vector if the checksum already matches, and `None`
+    /// if no unique pattern of at most `max_bits` flipped bits explains the
+    /// mismatch. At most two bits can be located; larger values of
+    /// `max_bits` behave like 2.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data.len()` differs from the length this locator was built
+    /// for.
+    pub fn locate(&self, data: &[u8], expected: u64, max_bits: u8) -> Option<Vec<usize>> {
+        assert_eq!(data.len(), self.len(), "message length mismatch");
+
+        let mut digest = Digest::new();
+        digest.write(data);
+        self.locate_syndrome(digest.sum64() ^ expected, max_bits)
+    }
+
+    fn locate_syndrome(&self, syndrome: u64, max_bits: u8) -> Option<Vec<usize>> {
+        if syndrome == 0 {
+            return Some(Vec::new());
+        }
+        let max_bits = max_bits.min(MAX_BITS);
+
+        if max_bits >= 1 {
+            match self.positions.get(&syndrome) {
+                Some(Some(i)) => return Some(vec![*i]),
+                Some(None) => return None,
+                None => {}
+            }
+        }
+
+        if max_bits >= 2 {
+            let mut found = None;
+            for (i, s) in self.syndromes.iter().enumerate() {
+                match self.positions.get(&(syndrome ^ s)) {
+                    Some(Some(j)) if *j > i => {
+                        if found.is_some() {
+                            return None;
+                        }
+                        found = Some(vec![i, *j]);
+                    }
+                    Some(None) => return None,
+                    _ => {}
+                }
+            }
+            return found;
+        }
+
+        None
+    }
+}
+
+/// Finds up to `max_bits` flipped bits in `data` given the CRC-64 it was
+/// expected to have.
+///
+/// See [`ErrorLocator::locate`] for details.
+pub fn locate_errors(data: &[u8], expected: u64, max_bits: u8) -> Option<Vec<usize>> {
+    let mut digest = Digest::new();
+    digest.write(data);
+    let syndrome = digest.sum64() ^ expected;
+    if syndrome == 0 {
+        return Some(Vec::new());
+    }
+    ErrorLocator::new(data.len()).locate_syndrome(syndrome, max_bits)
+}
+
+/// Like [`locate_errors`], but also flips the located bits in place.
+///
+/// `data` is left untouched if `None` is returned.
+pub fn correct_errors(data: &mut [u8], expected: u64, max_bits: u8) -> Option<Vec<usize>> {
+    let positions = locate_errors(data, expected, max_bits)?;
+    for p in &positions {
+        data[p / 8] ^= 1 << (p % 8);
+    }
+    Some(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn checksum(data: &[u8]) -> u64 {
+        let mut digest = Digest::new();
+        digest.write(data);
+        digest.sum64()
+    }
+
+    #[test]
+    fn test_no_error() {
+        let data = b"hello world!";
+        assert_eq!(locate_errors(data, checksum(data), 2), Some(vec![]));
+        assert_eq!(locate_errors(b"", 0, 2), Some(vec![]));
+    }
+
+    #[test]
+    fn test_too_many_errors() {
+        let original = [0x5a; 64];
+        let expected = checksum(&original);
+        let mut data = original;
+        data[0] ^= 1;
+        data[20] ^= 0x10;
+        data[63] ^= 0x80;
+        assert_eq!(locate_errors(&data, expected, 2), None);
+        data[63] ^= 0x80;
+        assert_eq!(locate_errors(&data, expected, 1), None);
+        assert_eq!(locate_errors(&data, expected, 0), None);
+    }
+
+    proptest! {
+        #[test]
+        fn single_bit(original in proptest::collection::vec(any::<u8>(), 1..512), seed in any::<usize>()) {
+            let expected = checksum(&original);
+            let bit = seed % (original.len() * 8);
+            let mut data = original.clone();
+            data[bit / 8] ^= 1 << (bit % 8);
+            prop_assert_eq!(correct_errors(&mut data, expected, 1), Some(vec![bit]));
+            prop_assert_eq!(data, original);
+        }
+
+        #[test]
+        fn double_bit(original in proptest::collection::vec(any::<u8>(), 1..256), a in any::<usize>(), b in any::<usize>()) {
+            let bits = original.len() * 8;
+            let (a, b) = (a % bits, b % bits);
+            prop_assume!(a != b);
+            let expected = checksum(&original);
+            let mut data = original.clone();
+            data[a / 8] ^= 1 << (a % 8);
+            data[b / 8] ^= 1 << (b % 8);
+            let locator = ErrorLocator::new(data.len());
+            prop_assert_eq!(locator.locate(&data, expected, 2), Some(vec![a.min(b), a.max(b)]));
+        }
+    }
+}
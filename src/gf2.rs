@@ -0,0 +1,327 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+//! Polynomial arithmetic over GF(2).
+//!
+//! Polynomials are stored as integers where bit `i` is the coefficient of
+//! xⁱ ("normal" notation, as opposed to the bit-reflected notation used by
+//! the CRC registers themselves). Multiplications go through the SIMD
+//! carryless multiplication instructions when the CPU supports them.
+//!
+//! ## Usage
+//!
+//! ```
+//! use crc64fast::gf2::{reflect, Poly64};
+//!
+//! let p = Poly64::ECMA;
+//! let x_127 = p.pow_mod(127);
+//! assert_eq!(reflect(x_127), 0xdabe_95af_c787_5f40);
+//! assert_eq!(p.mul_mod(x_127, p.inverse(x_127).unwrap()), 1);
+//! ```
+
+use super::pclmulqdq;
+use std::sync::OnceLock;
+
+/// Computes the carryless product of two 64-bit polynomials.
+pub fn clmul(a: u64, b: u64) -> u128 {
+    static CLMUL: OnceLock<fn(u64, u64) -> u128> = OnceLock::new();
+    (CLMUL.get_or_init(pclmulqdq::get_clmul))(a, b)
+}
+
+/// Computes the carryless product of two 64-bit polynomials without any SIMD
+/// instruction.
+pub(crate) fn poly_mul(a: u64, b: u64) -> u128 {
+    let mut res = 0;
+    for i in 0..64 {
+        if a & (1 << i) != 0 {
+            res ^= u128::from(b) << i;
+        }
+    }
+    res
+}
+
+/// Reverses the bit order of a 64-bit polynomial, converting between normal
+/// and reflected notations.
+pub const fn reflect(value: u64) -> u64 {
+    value.reverse_bits()
+}
+
+/// A degree-64 polynomial used as a modulus.
+///
+/// The x⁶⁴ term is implied and not stored, so the ECMA polynomial
+/// x⁶⁴ + x⁶² + x⁵⁷ + … + 1 is written as `0x42f0_e1eb_a9ea_3693`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Poly64 {
+    /// The polynomial without its x⁶⁴ term.
+    poly: u64,
+    /// The quotient x¹²⁸/P without its x⁶⁴ term, used for Barrett reduction.
+    mu: u64,
+}
+
+impl Poly64 {
+    /// The ECMA-182 polynomial, used by CRC-64/XZ.
+    pub const ECMA: Self = Self::new(0x42f0_e1eb_a9ea_3693);
+
+    /// Creates a modulus from its normal notation, omitting the x⁶⁴ term.
+    pub const fn new(poly: u64) -> Self {
+        // Long division of x¹²⁸ by x⁶⁴ + poly. The first quotient bit (x⁶⁴)
+        // is always set and leaves x⁶⁴·poly as the remainder.
+        let mut rem = (poly as u128) << 64;
+        let mut mu = 0;
+        let mut i = 64;
+        while i > 0 {
+            i -= 1;
+            if rem & (1 << (64 + i)) != 0 {
+                mu |= 1 << i;
+                rem ^= (1 << (64 + i)) | ((poly as u128) << i);
+            }
+        }
+        Self { poly, mu }
+    }
+
+    /// Creates a modulus from its reflected notation, omitting the x⁶⁴ term.
+    pub const fn from_reflected(reflected: u64) -> Self {
+        Self::new(reflect(reflected))
+    }
+
+    /// Returns the normal notation of this polynomial, omitting the x⁶⁴ term.
+    pub const fn poly(&self) -> u64 {
+        self.poly
+    }
+
+    /// Returns the reflected notation of this polynomial, omitting the x⁶⁴
+    /// term.
+    pub const fn reflected(&self) -> u64 {
+        reflect(self.poly)
+    }
+
+    /// Returns the quotient x¹²⁸/P in normal notation, omitting the x⁶⁴
+    /// term.
+    pub(crate) const fn mu(&self) -> u64 {
+        self.mu
+    }
+
+    /// Computes `value mod P` for a polynomial of degree below 128.
+    pub fn reduce(&self, value: u128) -> u64 {
+        let high = (value >> 64) as u64;
+        let low = value as u64;
+        // Barrett reduction: the quotient is (high · x¹²⁸/P) / x⁶⁴.
+        let quotient = high ^ (clmul(high, self.mu) >> 64) as u64;
+        low ^ clmul(quotient, self.poly) as u64
+    }
+
+    /// Computes `a · b mod P`.
+    pub fn mul_mod(&self, a: u64, b: u64) -> u64 {
+        self.reduce(clmul(a, b))
+    }
+
+    /// Computes `a · x mod P`.
+    pub(crate) const fn mul_x(&self, a: u64) -> u64 {
+        (a << 1) ^ if a >> 63 != 0 { self.poly } else { 0 }
+    }
+
+    /// Computes `xⁿ mod P`.
+    pub fn pow_mod(&self, n: u64) -> u64 {
+        let mut res = 1;
+        for i in (0..64 - n.leading_zeros()).rev() {
+            res = self.mul_mod(res, res);
+            if n & (1 << i) != 0 {
+                res = self.mul_x(res);
+            }
+        }
+        res
+    }
+
+    /// Computes the multiplicative inverse of `a` modulo P.
+    ///
+    /// Returns `None` if `a` shares a common factor with P (in particular
+    /// when `a` is zero).
+    pub fn inverse(&self, a: u64) -> Option<u64> {
+        // Extended Euclidean algorithm, tracking only the coefficient of `a`.
+        let full = 1 << 64 | u128::from(self.poly);
+        let (mut r0, mut r1) = (full, u128::from(a));
+        let (mut s0, mut s1) = (0, 1);
+        while r1 != 0 {
+            let (q, r) = div_rem(r0, r1);
+            let q = if q >> 64 != 0 { q ^ full } else { q } as u64;
+            (r0, r1) = (r1, r);
+            (s0, s1) = (s1, s0 ^ self.mul_mod(q, s1));
+        }
+        if r0 == 1 {
+            Some(s0)
+        } else {
+            None
+        }
+    }
+}
+
+/// A polynomial of any degree, stored as little-endian 64-bit words.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Poly(pub(crate) Vec<u64>);
+
+impl Poly {
+    /// Returns the degree, or `None` for the zero polynomial.
+    pub(crate) fn degree(&self) -> Option<usize> {
+        let (i, w) = self.0.iter().enumerate().rev().find(|(_, w)| **w != 0)?;
+        Some(i * 64 + 63 - w.leading_zeros() as usize)
+    }
+
+    /// Returns the polynomial as a `u128`, if its degree is less than 128.
+    pub(crate) fn to_u128(&self) -> Option<u128> {
+        if matches!(self.degree(), Some(d) if d >= 128) {
+            return None;
+        }
+        let word = |i: usize| u128::from(self.0.get(i).copied().unwrap_or(0));
+        Some(word(0) | word(1) << 64)
+    }
+
+    /// Computes `self += other · xˢʰⁱᶠᵗ`.
+    pub(crate) fn xor_shifted(&mut self, other: &Self, shift: usize) {
+        let (words, bits) = (shift / 64, shift % 64);
+        let len = other.0.len() + words + 1;
+        if self.0.len() < len {
+            self.0.resize(len, 0);
+        }
+        for (i, w) in other.0.iter().enumerate() {
+            self.0[i + words] ^= w << bits;
+            if bits != 0 {
+                self.0[i + words + 1] ^= w >> (64 - bits);
+            }
+        }
+    }
+
+    /// Computes the remainder of `self` divided by the non-zero polynomial
+    /// `other`.
+    fn rem(mut self, other: &Self) -> Self {
+        let d = other.degree().expect("division by zero polynomial");
+        while let Some(s) = self.degree().filter(|s| *s >= d) {
+            self.xor_shifted(other, s - d);
+        }
+        self
+    }
+
+    /// Computes the greatest common divisor of `self` and `other`.
+    pub(crate) fn gcd(self, other: Self) -> Self {
+        let (mut a, mut b) = (self, other);
+        while b.degree().is_some() {
+            let r = a.rem(&b);
+            a = b;
+            b = r;
+        }
+        // Drop leading zero words to keep later operations cheap.
+        let len = a.degree().map_or(0, |d| d / 64 + 1);
+        a.0.truncate(len);
+        a
+    }
+}
+
+/// Returns the degree of a non-zero polynomial.
+fn degree(a: u128) -> u32 {
+    127 - a.leading_zeros()
+}
+
+/// Divides `a` by the non-zero polynomial `b`, returning the quotient and
+/// remainder.
+pub(crate) fn div_rem(mut a: u128, b: u128) -> (u128, u128) {
+    let mut q = 0;
+    let db = degree(b);
+    while a != 0 && degree(a) >= db {
+        let shift = degree(a) - db;
+        q |= 1 << shift;
+        a ^= b << shift;
+    }
+    (q, a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_clmul() {
+        assert_eq!(
+            clmul(0x5a2d_8244_0f1e_3e50, 0xcae9_00d5_fed9_262f),
+            0x39ca_c5ca_fc66_6bf3_25bc_9dd4_c0f3_6330,
+        );
+    }
+
+    #[test]
+    fn test_constants() {
+        let p = Poly64::ECMA;
+        for (n, k) in [
+            (127, table::K_127),
+            (191, table::K_191),
+            (511, table::K_511),
+            (1023, table::K_1023),
+            (1087, table::K_1087),
+        ] {
+            assert_eq!(reflect(p.pow_mod(n)), k, "K_{}", n);
+        }
+        assert_eq!(p.pow_mod(0), 1);
+        assert_eq!(p.pow_mod(63), 1 << 63);
+        assert_eq!(p.pow_mod(64), p.poly());
+    }
+
+    #[test]
+    fn test_poly_mul() {
+        assert_eq!(
+            poly_mul(0x5a2d_8244_0f1e_3e50, 0xcae9_00d5_fed9_262f),
+            0x39ca_c5ca_fc66_6bf3_25bc_9dd4_c0f3_6330,
+        );
+    }
+
+    #[test]
+    fn test_mu() {
+        // x¹²⁸ = (x⁶⁴ + mu)·P + r where deg(r) < 64, so x¹²⁸ + x⁶⁴·P must be
+        // divisible by P up to a remainder.
+        let p = Poly64::ECMA;
+        let product = clmul(p.mu, p.poly) ^ (u128::from(p.poly) << 64) ^ (u128::from(p.mu) << 64);
+        assert_eq!(product >> 64, 0);
+    }
+
+    #[test]
+    fn test_poly_gcd() {
+        // (x⁶⁴ + ECMA)·(x + 1) and (x⁶⁴ + ECMA)·x³ share only x⁶⁴ + ECMA.
+        let p = Poly(vec![Poly64::ECMA.poly(), 1]);
+        let mut a = Poly::default();
+        a.xor_shifted(&p, 1);
+        a.xor_shifted(&p, 0);
+        let mut b = Poly::default();
+        b.xor_shifted(&p, 3);
+        let gcd = a.gcd(b);
+        assert_eq!(gcd.degree(), Some(64));
+        assert_eq!(
+            gcd.to_u128(),
+            Some(1 << 64 | u128::from(Poly64::ECMA.poly()))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn mul_mod_matches_portable(a in any::<u64>(), b in any::<u64>()) {
+            let p = Poly64::ECMA;
+            let mut expected = poly_mul(a, b);
+            for i in (64..128).rev() {
+                if expected & (1 << i) != 0 {
+                    expected ^= (1 << 64 | u128::from(p.poly())) << (i - 64);
+                }
+            }
+            prop_assert_eq!(p.mul_mod(a, b), expected as u64);
+        }
+
+        #[test]
+        fn inverse(a in any::<u64>()) {
+            let p = Poly64::ECMA;
+            if let Some(inv) = p.inverse(a) {
+                prop_assert_eq!(p.mul_mod(a, inv), 1);
+            }
+        }
+
+        #[test]
+        fn pow_mod_adds_exponents(m in 0..1u64 << 40, n in 0..1u64 << 40) {
+            let p = Poly64::ECMA;
+            prop_assert_eq!(p.pow_mod(m + n), p.mul_mod(p.pow_mod(m), p.pow_mod(n)));
+        }
+    }
+}
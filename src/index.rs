@@ -0,0 +1,137 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+//! CRC-64 values of arbitrary ranges of a message, from prefix checkpoints.
+//!
+//! The CRC of `data[start..end]` follows from the CRCs of the prefixes
+//! `data[..start]` and `data[..end]`, as `crc(..end) ⊕ crc(..start)·x^(8·len)`
+//! (see the [`combine`](crate::combine) module). [`CrcIndex`] records the CRC state
+//! every `stride` bytes, so that the state of any prefix only needs the
+//! bytes since the last checkpoint.
+
+use super::combine::shift;
+use super::{pclmulqdq, UpdateFn};
+
+/// Prefix checkpoints of a message, answering CRC-64-ECMA queries over any
+/// of its ranges.
+///
+/// A query reads at most `2 · stride` bytes of the message, and takes
+/// O(log `len`) carryless multiplications. The index takes 8 bytes per
+/// stride.
+///
+/// ```
+/// use crc64fast::index::CrcIndex;
+///
+/// let data = b"hello 123456789 world";
+/// let index = CrcIndex::build(data, 4);
+/// assert_eq!(index.crc_of_range(6, 15), 0x995d_c9bb_df19_39fa);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CrcIndex<'a> {
+    data: &'a [u8],
+    stride: usize,
+    computer: UpdateFn,
+    /// `states[i]` is the CRC state after the first `i * stride` bytes.
+    states: Vec<u64>,
+}
+
+impl<'a> CrcIndex<'a> {
+    /// Builds the index of `data`, with a checkpoint every `stride` bytes.
+    ///
+    /// This reads `data` once, with the implementation detected at runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stride` is 0.
+    pub fn build(data: &'a [u8], stride: usize) -> Self {
+        assert!(stride > 0, "stride must not be zero");
+        let computer = pclmulqdq::get_update();
+        let mut states = Vec::with_capacity(data.len() / stride + 1);
+        let mut state = !0;
+        states.push(state);
+        for chunk in data.chunks_exact(stride) {
+            state = computer(state, chunk);
+            states.push(state);
+        }
+        Self {
+            data,
+            stride,
+            computer,
+            states,
+        }
+    }
+
+    /// Returns the indexed message.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the distance between checkpoints.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns the CRC state after the first `pos` bytes.
+    fn state_at(&self, pos: usize) -> u64 {
+        let i = pos / self.stride;
+        (self.computer)(self.states[i], &self.data[i * self.stride..pos])
+    }
+
+    /// Computes the CRC-64-ECMA value of `data[start..end]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start > end` or if `end` is past the end of the message.
+    pub fn crc_of_range(&self, start: usize, end: usize) -> u64 {
+        assert!(
+            start <= end && end <= self.data.len(),
+            "range {}..{} out of bounds for length {}",
+            start,
+            end,
+            self.data.len()
+        );
+        if end - start <= self.stride {
+            return !(self.computer)(!0, &self.data[start..end]);
+        }
+        let crc_start = !self.state_at(start);
+        let crc_end = !self.state_at(end);
+        crc_end ^ shift(crc_start, (end - start) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crc::{Crc, CRC_64_XZ};
+    use proptest::prelude::*;
+
+    const CRC: Crc<u64> = Crc::<u64>::new(&CRC_64_XZ);
+
+    #[test]
+    fn test_whole_and_empty() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 7) as u8).collect();
+        let index = CrcIndex::build(&data, 64);
+        assert_eq!(index.crc_of_range(0, 1000), CRC.checksum(&data));
+        assert_eq!(index.crc_of_range(500, 500), 0);
+        assert_eq!(index.crc_of_range(1000, 1000), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_out_of_bounds() {
+        CrcIndex::build(&[0; 10], 4).crc_of_range(5, 11);
+    }
+
+    proptest! {
+        #[test]
+        fn ranges(
+            data in proptest::collection::vec(any::<u8>(), 0..8192),
+            stride in 1..1024usize,
+            bounds in any::<(prop::sample::Index, prop::sample::Index)>(),
+        ) {
+            let index = CrcIndex::build(&data, stride);
+            let (a, b) = (bounds.0.index(data.len() + 1), bounds.1.index(data.len() + 1));
+            let (start, end) = (a.min(b), a.max(b));
+            prop_assert_eq!(index.crc_of_range(start, end), CRC.checksum(&data[start..end]));
+        }
+    }
+}
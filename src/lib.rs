@@ -17,16 +17,56 @@
 //! let checksum = c.sum64();
 //! assert_eq!(checksum, 0x8483_c0fa_3260_7d61);
 //! ```
+//!
+//! ## Backend selection
+//!
+//! The fastest implementation supported by the CPU is detected at runtime.
+//! The `CRC64FAST_BACKEND` environment variable, read once when first
+//! needed, overrides the detection:
+//!
+//! * `table` forces the portable table-based implementation.
+//! * `simd` requires a SIMD implementation, and panics if the CPU does not
+//!   support any.
+//! * `auto`, the default, picks the fastest one.
+//!
+//! Any other value panics. Explicit selections are not affected: neither
+//! [`Digest::with_backend`], nor the [`backend`] types which do not perform
+//! runtime detection. [`backend::Auto`] honours `table` even when built with
+//! the target features of the SIMD implementation.
 
+pub mod analysis;
+pub mod backend;
+pub mod combine;
+pub mod correct;
+pub mod gf2;
+pub mod index;
+pub mod nvme_pi;
 mod pclmulqdq;
+pub mod reveng;
+pub mod rolling;
+mod selftest;
 mod table;
+pub mod tree;
+
+use std::fmt;
+use std::io::IoSlice;
+use std::sync::Arc;
 
 type UpdateFn = fn(u64, &[u8]) -> u64;
+type UpdateChunksFn = fn(u64, &mut dyn Iterator<Item = &[u8]>) -> u64;
+type CopyUpdateFn = fn(u64, &mut [u8], &[u8]) -> u64;
+type UpdateManyFn = fn(&mut [u64], &[&[u8]]);
+type UpdateWithFn = fn(&table::Params, u64, &[u8]) -> u64;
+type UpdateMultiFn<const N: usize> = fn(&mut [u64; N], &[&'static table::Params; N], &[u8]);
 
 /// Represents an in-progress CRC-64 computation.
+///
+/// The implementation is chosen by the [`Engine`](backend::Engine) `B`.
+/// By default, it is detected at runtime, see the [`backend`] module for
+/// static alternatives, which are created with [`Digest::from_backend`].
 #[derive(Clone)]
-pub struct Digest {
-    computer: UpdateFn,
+pub struct Digest<B: backend::Engine = backend::Dynamic> {
+    backend: B,
     state: u64,
 }
 
@@ -36,45 +76,513 @@ impl Digest {
     /// It will perform runtime CPU feature detection to determine which
     /// algorithm to choose.
     pub fn new() -> Self {
-        Self {
-            computer: pclmulqdq::get_update(),
-            state: !0,
-        }
+        Self::from_backend(backend::Dynamic::default())
     }
 
     /// Creates a new `Digest` using table-based algorithm.
     pub fn new_table() -> Self {
-        Self {
-            computer: table::update,
-            state: !0,
-        }
+        Self::from_backend(backend::Dynamic::with_computer(
+            table::update,
+            table::update_chunks,
+        ))
+    }
+
+    /// Creates a new `Digest` using the implementation `backend`, or returns
+    /// an error if the CPU does not support it.
+    ///
+    /// [`Backend::Table`] is supported everywhere.
+    pub fn with_backend(backend: Backend) -> Result<Self, Unsupported> {
+        let (computer, chunks) = pclmulqdq::get_update_for(backend).ok_or(Unsupported(backend))?;
+        Ok(Self::from_backend(backend::Dynamic::with_computer(
+            computer, chunks,
+        )))
+    }
+}
+
+impl Digest<backend::Verified> {
+    /// Creates a new `Digest` which computes every write with both the
+    /// implementation detected at runtime and the table-based one.
+    ///
+    /// # Panics
+    ///
+    /// [`write`](Self::write) panics if the implementations disagree.
+    pub fn new_verified() -> Self {
+        Self::from_backend(backend::Verified::new(None))
+    }
+
+    /// Like [`Digest::new_verified`], but calls `on_divergence` instead of
+    /// panicking when the implementations disagree. The digest then goes on
+    /// with the result of the table-based implementation.
+    pub fn new_verified_with<F>(on_divergence: F) -> Self
+    where
+        F: Fn(&backend::Divergence<'_>) + Send + Sync + 'static,
+    {
+        Self::from_backend(backend::Verified::new(Some(Arc::new(on_divergence))))
+    }
+}
+
+impl<B: backend::Engine> Digest<B> {
+    /// Creates a new `Digest` computing with `backend`.
+    pub fn from_backend(backend: B) -> Self {
+        Self { backend, state: !0 }
     }
 
     /// Writes some data into the digest.
+    #[inline]
     pub fn write(&mut self, bytes: &[u8]) {
-        self.state = (self.computer)(self.state, bytes);
+        self.state = self.backend.update(self.state, bytes);
+    }
+
+    /// Writes the concatenation of `bufs` into the digest.
+    ///
+    /// This is faster than writing each buffer in turn when they are short
+    /// or unaligned, since SIMD folding goes on across their boundaries.
+    pub fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) {
+        self.state = self
+            .backend
+            .update_chunks(self.state, &mut bufs.iter().map(|b| &**b));
     }
 
     /// Computes the current CRC-64-ECMA value.
+    #[inline]
     pub fn sum64(&self) -> u64 {
         !self.state
     }
 }
 
+/// Same as [`Digest::new`].
+///
+/// ```
+/// let mut c = crc64fast::Digest::default();
+/// c.write(b"123456789");
+/// assert_eq!(c.sum64(), 0x995d_c9bb_df19_39fa);
+/// ```
 impl Default for Digest {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// An implementation of CRC-64, as chosen by runtime CPU feature detection.
+///
+/// ```
+/// use crc64fast::{detected_backend, Backend, Digest};
+///
+/// println!("computing CRC-64 with {}", detected_backend());
+/// let mut c = Digest::with_backend(Backend::Table).unwrap();
+/// c.write(b"123456789");
+/// assert_eq!(c.sum64(), 0x995d_c9bb_df19_39fa);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+    /// The table-based implementation, available on every CPU.
+    Table,
+    /// PCLMULQDQ and SSE 4.1 on x86 and x86_64.
+    Pclmulqdq,
+    /// PMULL on AArch64.
+    Pmull,
+    /// PMULL on AArch64, with the EOR3 instruction of the SHA3 extension.
+    PmullEor3,
+    /// VMULL.P64 on 32-bit ARM.
+    Vmull,
+    /// VPMSUMD on POWER8 and later.
+    Vpmsumd,
+    /// VGFMG on z13 and later.
+    Vgfm,
+    /// The Zbc extension on RISC-V.
+    Zbc,
+    /// The Zvbc extension on RISC-V.
+    Zvbc,
+    /// The platform-agnostic emulation of the SIMD implementations, enabled
+    /// by the `fake-simd` feature for testing.
+    FakeSimd,
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Table => "table",
+            Self::Pclmulqdq => "pclmulqdq",
+            Self::Pmull => "pmull",
+            Self::PmullEor3 => "pmull-eor3",
+            Self::Vmull => "vmull",
+            Self::Vpmsumd => "vpmsumd",
+            Self::Vgfm => "vgfm",
+            Self::Zbc => "zbc",
+            Self::Zvbc => "zvbc",
+            Self::FakeSimd => "fake-simd",
+        })
+    }
+}
+
+/// Returns the fastest [`Backend`] supported by the CPU, i.e. the one used
+/// by [`Digest::new`].
+pub fn detected_backend() -> Backend {
+    pclmulqdq::detected_backend()
+}
+
+/// Error returned by [`Digest::with_backend`] when the CPU does not support
+/// the requested backend.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Unsupported(pub Backend);
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the {} backend is not supported by this CPU", self.0)
+    }
+}
+
+impl std::error::Error for Unsupported {}
+
+/// Checks that the implementation used by [`Digest::new`] computes correct
+/// checksums, and returns its backend.
+///
+/// Besides [`Digest`], this covers the functions behind [`checksum_chunks`],
+/// [`copy_and_checksum`], [`checksum_many`] (and so [`block_checksums`] and
+/// [`verify_blocks`]), [`MultiDigest`] and the 64-bit guards of [`nvme_pi`],
+/// the latter two with the NVME polynomial too.
+///
+/// This takes about a millisecond, and is meant to be run once at startup,
+/// before trusting the checksums of the process.
+///
+/// ```
+/// let backend = crc64fast::self_test().expect("CRC-64 self-test failed");
+/// println!("computing CRC-64 with {}", backend);
+/// ```
+pub fn self_test() -> Result<Backend, SelfTestError> {
+    selftest::run()
+}
+
+/// Error returned by [`self_test`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SelfTestError {
+    /// The CRC of one of the known messages is wrong.
+    KnownVector {
+        /// The backend under test.
+        backend: Backend,
+        /// The function of the backend under test.
+        function: &'static str,
+        /// The length of the message.
+        len: usize,
+        /// The known CRC value.
+        expected: u64,
+        /// The computed CRC value.
+        actual: u64,
+    },
+    /// The CRC state differs from the one computed by the table-based
+    /// implementation.
+    Mismatch {
+        /// The backend under test.
+        backend: Backend,
+        /// The function of the backend under test.
+        function: &'static str,
+        /// The offset of the message from a 16-byte boundary.
+        offset: usize,
+        /// The length of the message.
+        len: usize,
+        /// The state computed by the table-based implementation.
+        expected: u64,
+        /// The state computed by the backend.
+        actual: u64,
+    },
+    /// The bytes copied while checksumming differ from the source.
+    Copy {
+        /// The backend under test.
+        backend: Backend,
+        /// The offset of the message from a 16-byte boundary.
+        offset: usize,
+        /// The length of the message.
+        len: usize,
+    },
+}
+
+impl fmt::Display for SelfTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KnownVector {
+                backend,
+                function,
+                len,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} backend computed {:#018x} instead of {:#018x} for a known {}-byte message with `{}`",
+                backend, actual, expected, len, function
+            ),
+            Self::Mismatch {
+                backend,
+                function,
+                offset,
+                len,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} backend computed state {:#018x} instead of {:#018x} for {} bytes at offset {} with `{}`",
+                backend, actual, expected, len, offset, function
+            ),
+            Self::Copy {
+                backend,
+                offset,
+                len,
+            } => write!(
+                f,
+                "{} backend copied {} bytes at offset {} wrongly",
+                backend, len, offset
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SelfTestError {}
+
+/// A CRC-64 algorithm which can be computed by [`MultiDigest`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Variant {
+    /// CRC-64/XZ, using the ECMA polynomial. This is what [`Digest`]
+    /// computes.
+    Xz,
+    /// CRC-64/NVME, using the polynomial `0xad93_d235_94c9_3659`. Also known
+    /// as the CRC-64 of the S3 checksum API.
+    Nvme,
+}
+
+impl Variant {
+    fn params(self) -> &'static table::Params {
+        match self {
+            Self::Xz => &table::ECMA,
+            Self::Nvme => &table::NVME,
+        }
+    }
+}
+
+/// Represents several in-progress CRC-64 computations over the same data.
+///
+/// Short writes, under 2 KiB, and long ones, of 4 MiB or more, read the data
+/// only once: each block is folded into the accumulators of every variant as
+/// soon as it is loaded. This overlaps the folds of short data, and saves
+/// memory bandwidth on data not in the cache, making it faster than `N`
+/// digests. Writes in between stay in the cache, so each variant is computed
+/// in its own pass, as fast as separate digests would be.
+///
+/// ```
+/// use crc64fast::{MultiDigest, Variant};
+///
+/// let mut c = MultiDigest::new([Variant::Xz, Variant::Nvme]);
+/// c.write(b"123456789");
+/// assert_eq!(c.sum64(), [0x995d_c9bb_df19_39fa, 0xae8b_1486_0a79_9888]);
+/// ```
+#[derive(Clone)]
+pub struct MultiDigest<const N: usize> {
+    computer: UpdateMultiFn<N>,
+    params: [&'static table::Params; N],
+    states: [u64; N],
+}
+
+impl<const N: usize> MultiDigest<N> {
+    /// Creates a new `MultiDigest` computing each of `variants`.
+    ///
+    /// It will perform runtime CPU feature detection to determine which
+    /// algorithm to choose.
+    pub fn new(variants: [Variant; N]) -> Self {
+        Self {
+            computer: pclmulqdq::get_update_multi(),
+            params: variants.map(Variant::params),
+            states: [!0; N],
+        }
+    }
+
+    /// Writes some data into the digest.
+    pub fn write(&mut self, bytes: &[u8]) {
+        (self.computer)(&mut self.states, &self.params, bytes);
+    }
+
+    /// Computes the current CRC-64 values, in the order the variants were
+    /// given to [`MultiDigest::new`].
+    pub fn sum64(&self) -> [u64; N] {
+        self.states.map(|state| !state)
+    }
+}
+
+/// Computes the CRC-64-ECMA value of each message independently.
+///
+/// The messages are read unaligned, so that all but the shortest ones (under
+/// 16 bytes) are folded with SIMD from their first to their last byte. This
+/// makes short messages several times faster than checksumming them one by
+/// one with [`Digest`], whose table-based head and tail dominate below a few
+/// hundred bytes. Consecutive messages are also folded eight at a time, side
+/// by side, so that the latency of each fold is hidden behind the others.
+/// Long messages are about as fast as with [`Digest`].
+pub fn checksum_many(messages: &[&[u8]]) -> Vec<u64> {
+    let mut out = vec![0; messages.len()];
+    checksum_many_into(messages, &mut out);
+    out
+}
+
+/// Like [`checksum_many`], but writes the CRC-64-ECMA values into `out`.
+///
+/// # Panics
+///
+/// Panics if `messages` and `out` have different lengths.
+pub fn checksum_many_into(messages: &[&[u8]], out: &mut [u64]) {
+    assert_eq!(
+        messages.len(),
+        out.len(),
+        "output length must match the number of messages"
+    );
+    out.fill(!0);
+    (pclmulqdq::get_update_many())(out, messages);
+    for state in out {
+        *state = !*state;
+    }
+}
+
+/// Computes the CRC-64-ECMA value of each `block_size`-byte block of
+/// `data`. The last block may be shorter.
+///
+/// The blocks are folded several at a time, side by side, like
+/// [`checksum_many`] does, without gathering them.
+///
+/// ```
+/// let crcs = crc64fast::block_checksums(b"123456789123456789", 9);
+/// assert_eq!(crcs, [0x995d_c9bb_df19_39fa; 2]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `block_size` is 0.
+pub fn block_checksums(data: &[u8], block_size: usize) -> Vec<u64> {
+    assert!(block_size > 0, "block size must not be zero");
+    let mut out = Vec::with_capacity(data.chunks(block_size).len());
+    for_each_block_checksum(data, block_size, |_, crc| out.push(crc));
+    out
+}
+
+/// Checks each `block_size`-byte block of `data` against its expected
+/// CRC-64-ECMA value in `expected`, and returns the indices of the blocks
+/// which do not match.
+///
+/// ```
+/// let mut data = *b"123456789123456789";
+/// let crcs = crc64fast::block_checksums(&data, 9);
+/// data[12] ^= 1;
+/// assert_eq!(crc64fast::verify_blocks(&data, 9, &crcs), Err(vec![1]));
+/// ```
+///
+/// # Panics
+///
+/// Panics if `block_size` is 0, or if `expected` does not have one value
+/// per block.
+pub fn verify_blocks(data: &[u8], block_size: usize, expected: &[u64]) -> Result<(), Vec<usize>> {
+    assert!(block_size > 0, "block size must not be zero");
+    assert_eq!(
+        data.chunks(block_size).len(),
+        expected.len(),
+        "expected values must match the number of blocks"
+    );
+    let mut corrupt = vec![];
+    for_each_block_checksum(data, block_size, |i, crc| {
+        if crc != expected[i] {
+            corrupt.push(i);
+        }
+    });
+    if corrupt.is_empty() {
+        Ok(())
+    } else {
+        Err(corrupt)
+    }
+}
+
+/// Calls `f` with the index and the CRC-64-ECMA value of each
+/// `block_size`-byte block of `data`, in order.
+///
+/// The blocks are handed to the multi-message implementation in batches
+/// referenced from the stack, so that nothing is allocated.
+fn for_each_block_checksum(data: &[u8], block_size: usize, mut f: impl FnMut(usize, u64)) {
+    /// The number of blocks checksummed per call.
+    const BATCH: usize = 64;
+
+    let update_many = pclmulqdq::get_update_many();
+    let mut blocks = data.chunks(block_size);
+    let mut index = 0;
+    loop {
+        let mut batch: [&[u8]; BATCH] = [&[]; BATCH];
+        let len = batch
+            .iter_mut()
+            .zip(&mut blocks)
+            .map(|(b, block)| *b = block)
+            .count();
+        if len == 0 {
+            break;
+        }
+        let mut states = [!0; BATCH];
+        update_many(&mut states[..len], &batch[..len]);
+        for state in &states[..len] {
+            f(index, !state);
+            index += 1;
+        }
+    }
+}
+
+/// Computes the CRC-64-ECMA value of the concatenation of `chunks`.
+///
+/// Like [`Digest::write_vectored`], SIMD folding goes on across the chunk
+/// boundaries, so that chains of short or unaligned buffers are checksummed
+/// about as fast as a single contiguous one.
+///
+/// ```
+/// let ring = b"789123456";
+/// let (tail, head) = ring.split_at(3);
+/// assert_eq!(crc64fast::checksum_chunks([head, tail]), 0x995d_c9bb_df19_39fa);
+/// ```
+pub fn checksum_chunks<'a>(chunks: impl IntoIterator<Item = &'a [u8]>) -> u64 {
+    !(pclmulqdq::get_update_chunks())(!0, &mut chunks.into_iter())
+}
+
+/// Copies `src` into `dst` while computing its CRC-64-ECMA value.
+///
+/// `state` is the CRC-64-ECMA value of the data preceding `src`, or 0 to
+/// start a new checksum, and the value including `src` is returned. This
+/// reads `src` only once, which is faster than copying it and then
+/// checksumming either copy when the data is not in the cache.
+///
+/// ```
+/// let mut page = [0; 9];
+/// let crc = crc64fast::copy_and_checksum(&mut page[..4], b"1234", 0);
+/// let crc = crc64fast::copy_and_checksum(&mut page[4..], b"56789", crc);
+/// assert_eq!(&page, b"123456789");
+/// assert_eq!(crc, 0x995d_c9bb_df19_39fa);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `dst` and `src` have different lengths.
+pub fn copy_and_checksum(dst: &mut [u8], src: &[u8], state: u64) -> u64 {
+    assert_eq!(
+        dst.len(),
+        src.len(),
+        "destination and source slices have different lengths"
+    );
+    !(pclmulqdq::get_copy_update())(!state, dst, src)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Digest;
-    use crc::{Crc, CRC_64_XZ};
+    use super::backend::{Auto, Simd, Table};
+    use super::selftest::{NVME_VECTORS, STANDARD_VECTORS};
+    use super::{
+        block_checksums, checksum_chunks, checksum_many, copy_and_checksum, detected_backend,
+        verify_blocks, Backend, Digest, MultiDigest, Unsupported, Variant,
+    };
+    use crc::{Crc, CRC_64_NVME, CRC_64_XZ};
     use proptest::collection::size_range;
     use proptest::prelude::*;
+    use std::io::IoSlice;
 
     const CRC: Crc<u64> = Crc::<u64>::new(&CRC_64_XZ);
+    const CRC_NVME: Crc<u64> = Crc::<u64>::new(&CRC_64_NVME);
 
     #[test]
     fn test_standard_vectors() {
@@ -99,6 +607,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_self_test_vectors() {
+        for (input, result) in STANDARD_VECTORS {
+            assert_eq!(CRC.checksum(input), *result, "test case {:x?}", input);
+        }
+        for (input, result) in NVME_VECTORS {
+            assert_eq!(CRC_NVME.checksum(input), *result, "test case {:x?}", input);
+            let mut hasher = MultiDigest::new([Variant::Nvme]);
+            hasher.write(input);
+            assert_eq!(hasher.sum64(), [*result], "test case {:x?}", input);
+        }
+    }
+
+    #[test]
+    fn test_with_backend() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 7) as u8).collect();
+        let expected = CRC.checksum(&data);
+        assert!(Digest::with_backend(detected_backend()).is_ok());
+        for backend in [
+            Backend::Table,
+            Backend::Pclmulqdq,
+            Backend::Pmull,
+            Backend::PmullEor3,
+            Backend::Vmull,
+            Backend::Vpmsumd,
+            Backend::Vgfm,
+            Backend::Zbc,
+            Backend::Zvbc,
+            Backend::FakeSimd,
+        ] {
+            match Digest::with_backend(backend) {
+                Ok(mut digest) => {
+                    digest.write(&data);
+                    assert_eq!(digest.sum64(), expected, "{}", backend);
+                }
+                Err(e) => {
+                    assert_ne!(backend, Backend::Table);
+                    assert_ne!(backend, detected_backend());
+                    assert_eq!(e, Unsupported(backend));
+                }
+            }
+        }
+        if cfg!(feature = "fake-simd") {
+            assert_eq!(detected_backend(), Backend::FakeSimd);
+        }
+    }
+
     fn any_buffer() -> <Box<[u8]> as Arbitrary>::Strategy {
         any_with::<Box<[u8]>>(size_range(..65536).lift())
     }
@@ -120,6 +675,29 @@ mod tests {
             prop_assert_eq!(hasher.sum64(), CRC.checksum(&bytes));
         }
 
+        #[test]
+        fn static_backends((bytes, split_index) in bytes_and_split_index()) {
+            let (left, right) = bytes.split_at(split_index);
+            let expected = CRC.checksum(&bytes);
+
+            let mut table = Digest::from_backend(Table);
+            table.write(left);
+            table.write(right);
+            prop_assert_eq!(table.sum64(), expected);
+
+            let mut auto = Digest::from_backend(Auto::new());
+            auto.write(left);
+            auto.write(right);
+            prop_assert_eq!(auto.sum64(), expected);
+
+            if let Some(backend) = Simd::new() {
+                let mut simd = Digest::from_backend(backend);
+                simd.write(left);
+                simd.write(right);
+                prop_assert_eq!(simd.sum64(), expected);
+            }
+        }
+
         #[test]
         fn concatenation((bytes, split_index) in bytes_and_split_index()) {
             let mut hasher_1 = Digest::new();
@@ -131,6 +709,103 @@ mod tests {
             prop_assert_eq!(hasher_1.sum64(), hasher_2.sum64());
         }
 
+        #[test]
+        fn chunks(bytes in any_buffer(), splits in proptest::collection::vec(0..4096usize, 0..32)) {
+            let mut chunks = vec![];
+            let mut rest = &bytes[..];
+            for split in splits {
+                let (chunk, tail) = rest.split_at(split.min(rest.len()));
+                chunks.push(chunk);
+                rest = tail;
+            }
+            chunks.push(rest);
+            let expected = CRC.checksum(&bytes);
+            prop_assert_eq!(checksum_chunks(chunks.iter().copied()), expected);
+
+            let bufs: Vec<IoSlice<'_>> = chunks.iter().map(|c| IoSlice::new(c)).collect();
+            let mut digest = Digest::new();
+            digest.write_vectored(&bufs);
+            prop_assert_eq!(digest.sum64(), expected);
+            let mut auto = Digest::from_backend(Auto::new());
+            auto.write_vectored(&bufs);
+            prop_assert_eq!(auto.sum64(), expected);
+            if let Some(backend) = Simd::new() {
+                let mut simd = Digest::from_backend(backend);
+                simd.write_vectored(&bufs);
+                prop_assert_eq!(simd.sum64(), expected);
+            }
+        }
+
+        #[test]
+        fn copy((bytes, split_index) in bytes_and_split_index(), offset in 0..16usize) {
+            let (left, right) = bytes.split_at(split_index);
+            let mut dst = vec![0; bytes.len() + offset];
+            let dst = &mut dst[offset..];
+            let (dst_left, dst_right) = dst.split_at_mut(split_index);
+            let crc = copy_and_checksum(dst_left, left, 0);
+            let crc = copy_and_checksum(dst_right, right, crc);
+            prop_assert_eq!(crc, CRC.checksum(&bytes));
+            prop_assert_eq!(&*dst, &*bytes);
+        }
+
+        #[test]
+        fn blocks(
+            bytes in any_buffer(),
+            block_size in prop::sample::select(vec![1, 100, 512, 4096]),
+            corrupt in proptest::collection::vec(any::<prop::sample::Index>(), 0..4),
+        ) {
+            let crcs = block_checksums(&bytes, block_size);
+            let expected: Vec<u64> = bytes.chunks(block_size).map(|b| CRC.checksum(b)).collect();
+            prop_assert_eq!(&crcs, &expected);
+            prop_assert_eq!(verify_blocks(&bytes, block_size, &crcs), Ok(()));
+            if bytes.is_empty() {
+                return Ok(());
+            }
+
+            let mut bytes = bytes.into_vec();
+            let mut blocks: Vec<usize> = corrupt
+                .iter()
+                .map(|i| {
+                    let pos = i.index(bytes.len());
+                    bytes[pos] ^= 0x80;
+                    pos / block_size
+                })
+                .collect();
+            blocks.sort_unstable();
+            blocks.dedup();
+            // flipping the same bit twice leaves the block intact.
+            blocks.retain(|b| CRC.checksum(bytes.chunks(block_size).nth(*b).unwrap()) != crcs[*b]);
+            let res = verify_blocks(&bytes, block_size, &crcs);
+            if blocks.is_empty() {
+                prop_assert_eq!(res, Ok(()));
+            } else {
+                prop_assert_eq!(res, Err(blocks));
+            }
+        }
+
+        #[test]
+        fn many(messages in proptest::collection::vec(any_with::<Box<[u8]>>(size_range(..4096).lift()), 0..12)) {
+            let messages: Vec<&[u8]> = messages.iter().map(|m| &**m).collect();
+            let expected: Vec<u64> = messages.iter().map(|m| CRC.checksum(m)).collect();
+            prop_assert_eq!(checksum_many(&messages), expected);
+        }
+
+        #[test]
+        fn multi(left in any_buffer(), right in any_buffer()) {
+            let mut digest = MultiDigest::new([Variant::Xz, Variant::Nvme, Variant::Xz]);
+            digest.write(&left);
+            digest.write(&right);
+
+            let mut xz = CRC.digest();
+            xz.update(&left);
+            xz.update(&right);
+            let xz = xz.finalize();
+            let mut nvme = CRC_NVME.digest();
+            nvme.update(&left);
+            nvme.update(&right);
+            prop_assert_eq!(digest.sum64(), [xz, nvme.finalize(), xz]);
+        }
+
         #[test]
         fn state_cloning(left in any_buffer(), right in any_buffer()) {
             let mut hasher_1 = Digest::new();
@@ -0,0 +1,829 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+//! NVMe end-to-end data protection information (PI).
+//!
+//! Each logical block is followed by metadata holding its protection
+//! information: a guard (a CRC of the block), an application tag, and a
+//! storage and reference tag field. The NVM Command Set defines three PI
+//! formats, differing by the size of the guard:
+//!
+//! * [`PiFormat::Guard16`]: 8 bytes, with a CRC-16 T10-DIF guard and a
+//!   32-bit storage and reference tag field.
+//! * [`PiFormat::Guard32`]: 16 bytes, with a CRC-32C guard and an 80-bit
+//!   storage and reference tag field.
+//! * [`PiFormat::Guard64`]: 16 bytes, with a CRC-64/NVME guard and a 48-bit
+//!   storage and reference tag field.
+//!
+//! The most significant bits of the storage and reference tag field hold
+//! the storage tag, and the rest the reference tag, which is incremented
+//! for every block. All the fields are big-endian.
+//!
+//! The metadata is either interleaved with the data (extended logical
+//! blocks), or kept in a separate buffer. When it is larger than the PI, the
+//! PI occupies its first or last bytes, and in the latter case the guard
+//! also covers the metadata bytes before it.
+//!
+//! The CRC-64/NVME guards are computed with the SIMD implementation detected
+//! at runtime. The CRC-16 and CRC-32C guards are computed with slicing-by-8
+//! lookup tables, about an order of magnitude slower than the CRC-64 ones on
+//! CPUs with carryless multiplication.
+//!
+//! ```
+//! use crc64fast::nvme_pi::{self, Checks, Layout, PiError, PiFormat, Tags};
+//!
+//! let layout = Layout::new(PiFormat::Guard64, 512);
+//! let mut buf = vec![0; 4 * layout.extended_block_size()];
+//! let tags = Tags {
+//!     app_tag: 0x1234,
+//!     storage_tag: 0,
+//!     ref_tag: 1000,
+//! };
+//! nvme_pi::generate(&layout, &mut buf, tags);
+//! assert_eq!(nvme_pi::verify(&layout, &buf, tags, &Checks::default()), Ok(()));
+//!
+//! buf[2 * 528 + 7] ^= 1;
+//! let errors = nvme_pi::verify(&layout, &buf, tags, &Checks::default()).unwrap_err();
+//! assert!(matches!(errors[..], [PiError::Guard { block: 2, .. }]));
+//! ```
+
+use super::{pclmulqdq, table, UpdateWithFn};
+use std::fmt;
+
+/// The format of the protection information, named after the size of its
+/// guard.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PiFormat {
+    /// 16b Guard Protection Information, with a CRC-16 T10-DIF guard.
+    Guard16,
+    /// 32b Guard Protection Information, with a CRC-32C guard.
+    Guard32,
+    /// 64b Guard Protection Information, with a CRC-64/NVME guard.
+    Guard64,
+}
+
+impl PiFormat {
+    /// Returns the size of the protection information, in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            Self::Guard16 => 8,
+            Self::Guard32 | Self::Guard64 => 16,
+        }
+    }
+
+    /// Returns the size of the guard, in bytes.
+    fn guard_size(self) -> usize {
+        match self {
+            Self::Guard16 => 2,
+            Self::Guard32 => 4,
+            Self::Guard64 => 8,
+        }
+    }
+
+    /// Returns the size of the storage and reference tag field, in bits.
+    pub fn tag_bits(self) -> u32 {
+        (self.size() - self.guard_size() - 2) as u32 * 8
+    }
+}
+
+/// The layout of logical blocks and their metadata.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Layout {
+    format: PiFormat,
+    block_size: usize,
+    metadata_size: usize,
+    pi_first: bool,
+    storage_tag_bits: u32,
+}
+
+impl Layout {
+    /// Creates the layout of `block_size`-byte logical blocks, with metadata
+    /// only holding protection information of `format`.
+    ///
+    /// The storage tag is empty, except for [`PiFormat::Guard32`] whose
+    /// reference tag cannot be larger than 64 bits, and which has a 16-bit
+    /// storage tag by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is 0.
+    pub fn new(format: PiFormat, block_size: usize) -> Self {
+        assert!(block_size > 0, "block size must not be zero");
+        Self {
+            format,
+            block_size,
+            metadata_size: format.size(),
+            pi_first: false,
+            storage_tag_bits: format.tag_bits().saturating_sub(64),
+        }
+    }
+
+    /// Uses `metadata_size` bytes of metadata per block.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the metadata is smaller than the protection information.
+    pub fn with_metadata_size(mut self, metadata_size: usize) -> Self {
+        assert!(
+            metadata_size >= self.format.size(),
+            "metadata must hold the protection information"
+        );
+        self.metadata_size = metadata_size;
+        self
+    }
+
+    /// Puts the protection information in the first bytes of the metadata,
+    /// instead of the last ones.
+    pub fn with_pi_first(mut self) -> Self {
+        self.pi_first = true;
+        self
+    }
+
+    /// Uses the `bits` most significant bits of the storage and reference
+    /// tag field as the storage tag.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` exceeds the size of the field, or leaves more than
+    /// 64 bits to the reference tag.
+    pub fn with_storage_tag_bits(mut self, bits: u32) -> Self {
+        let tag_bits = self.format.tag_bits();
+        assert!(
+            bits <= tag_bits && tag_bits - bits <= 64 && bits <= 64,
+            "invalid storage tag size"
+        );
+        self.storage_tag_bits = bits;
+        self
+    }
+
+    /// Returns the format of the protection information.
+    pub fn format(&self) -> PiFormat {
+        self.format
+    }
+
+    /// Returns the size of the logical block data, in bytes.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Returns the size of the metadata of a block, in bytes.
+    pub fn metadata_size(&self) -> usize {
+        self.metadata_size
+    }
+
+    /// Returns the size of a block interleaved with its metadata.
+    pub fn extended_block_size(&self) -> usize {
+        self.block_size + self.metadata_size
+    }
+
+    /// Returns the offset of the protection information in the metadata.
+    fn pi_offset(&self) -> usize {
+        if self.pi_first {
+            0
+        } else {
+            self.metadata_size - self.format.size()
+        }
+    }
+
+    fn ref_tag_bits(&self) -> u32 {
+        self.format.tag_bits() - self.storage_tag_bits
+    }
+}
+
+/// The tags of the first block of a buffer.
+///
+/// The reference tag is incremented for every following block, wrapping
+/// around at the size of the field. The tags are truncated to the sizes of
+/// their fields.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Tags {
+    /// The application tag.
+    pub app_tag: u16,
+    /// The storage tag.
+    pub storage_tag: u64,
+    /// The reference tag of the first block.
+    pub ref_tag: u64,
+}
+
+/// The fields checked by [`verify`].
+///
+/// Blocks whose application tag is `0xffff` are never checked, like with
+/// protection types 1 and 2.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Checks {
+    /// Whether to check the guard.
+    pub guard: bool,
+    /// The bits of the application tag to check.
+    pub app_tag_mask: u16,
+    /// Whether to check the storage tag.
+    pub storage_tag: bool,
+    /// Whether to check the reference tag.
+    pub ref_tag: bool,
+}
+
+impl Default for Checks {
+    /// Checks every field.
+    fn default() -> Self {
+        Self {
+            guard: true,
+            app_tag_mask: 0xffff,
+            storage_tag: true,
+            ref_tag: true,
+        }
+    }
+}
+
+/// A protection information mismatch found by [`verify`], in the block of
+/// index `block` of the buffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PiError {
+    /// The guard does not match the data.
+    Guard {
+        /// The index of the block.
+        block: usize,
+        /// The guard computed from the data.
+        expected: u64,
+        /// The guard stored in the protection information.
+        actual: u64,
+    },
+    /// The application tag does not match.
+    AppTag {
+        /// The index of the block.
+        block: usize,
+        /// The expected application tag.
+        expected: u16,
+        /// The stored application tag.
+        actual: u16,
+    },
+    /// The storage tag does not match.
+    StorageTag {
+        /// The index of the block.
+        block: usize,
+        /// The expected storage tag.
+        expected: u64,
+        /// The stored storage tag.
+        actual: u64,
+    },
+    /// The reference tag does not match.
+    RefTag {
+        /// The index of the block.
+        block: usize,
+        /// The expected reference tag.
+        expected: u64,
+        /// The stored reference tag.
+        actual: u64,
+    },
+}
+
+impl fmt::Display for PiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Guard {
+                block,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "block {}: guard {:#x} does not match the data, expected {:#x}",
+                block, actual, expected
+            ),
+            Self::AppTag {
+                block,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "block {}: application tag {:#x}, expected {:#x}",
+                block, actual, expected
+            ),
+            Self::StorageTag {
+                block,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "block {}: storage tag {:#x}, expected {:#x}",
+                block, actual, expected
+            ),
+            Self::RefTag {
+                block,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "block {}: reference tag {:#x}, expected {:#x}",
+                block, actual, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PiError {}
+
+/// Generates the protection information of the extended logical blocks in
+/// `buf`, each made of the data followed by the metadata.
+///
+/// # Panics
+///
+/// Panics if `buf` is not made of whole extended blocks.
+pub fn generate(layout: &Layout, buf: &mut [u8], tags: Tags) {
+    let ext = layout.extended_block_size();
+    assert_eq!(buf.len() % ext, 0, "buffer must hold whole blocks");
+    let blocks = buf.chunks_exact_mut(ext).map(|b| {
+        let (data, metadata) = b.split_at_mut(layout.block_size);
+        (&*data, metadata)
+    });
+    generate_blocks(layout, blocks, tags);
+}
+
+/// Generates the protection information of the logical blocks in `data`
+/// into the separate `metadata` buffer.
+///
+/// # Panics
+///
+/// Panics if `data` is not made of whole blocks, or if `metadata` does not
+/// hold the metadata of every block.
+pub fn generate_separate(layout: &Layout, data: &[u8], metadata: &mut [u8], tags: Tags) {
+    check_separate(layout, data, metadata);
+    let blocks = data
+        .chunks_exact(layout.block_size)
+        .zip(metadata.chunks_exact_mut(layout.metadata_size));
+    generate_blocks(layout, blocks, tags);
+}
+
+/// Verifies the protection information of the extended logical blocks in
+/// `buf` against the data and `expected` tags, and returns the mismatches.
+///
+/// # Panics
+///
+/// Panics if `buf` is not made of whole extended blocks.
+pub fn verify(
+    layout: &Layout,
+    buf: &[u8],
+    expected: Tags,
+    checks: &Checks,
+) -> Result<(), Vec<PiError>> {
+    let ext = layout.extended_block_size();
+    assert_eq!(buf.len() % ext, 0, "buffer must hold whole blocks");
+    let blocks = buf.chunks_exact(ext).map(|b| b.split_at(layout.block_size));
+    verify_blocks(layout, blocks, expected, checks)
+}
+
+/// Like [`verify`], for logical blocks in `data` whose metadata is in the
+/// separate `metadata` buffer.
+///
+/// # Panics
+///
+/// Panics if `data` is not made of whole blocks, or if `metadata` does not
+/// hold the metadata of every block.
+pub fn verify_separate(
+    layout: &Layout,
+    data: &[u8],
+    metadata: &[u8],
+    expected: Tags,
+    checks: &Checks,
+) -> Result<(), Vec<PiError>> {
+    check_separate(layout, data, metadata);
+    let blocks = data
+        .chunks_exact(layout.block_size)
+        .zip(metadata.chunks_exact(layout.metadata_size));
+    verify_blocks(layout, blocks, expected, checks)
+}
+
+fn check_separate(layout: &Layout, data: &[u8], metadata: &[u8]) {
+    assert_eq!(
+        data.len() % layout.block_size,
+        0,
+        "buffer must hold whole blocks"
+    );
+    assert_eq!(
+        metadata.len(),
+        data.len() / layout.block_size * layout.metadata_size,
+        "metadata length must match the number of blocks"
+    );
+}
+
+/// The protection information of a block.
+struct Pi {
+    guard: u64,
+    app_tag: u16,
+    storage_tag: u64,
+    ref_tag: u64,
+}
+
+/// Computes the guards and encodes the protection information of a layout.
+struct Codec<'a> {
+    layout: &'a Layout,
+    update: UpdateWithFn,
+}
+
+impl<'a> Codec<'a> {
+    fn new(layout: &'a Layout) -> Self {
+        Self {
+            layout,
+            update: pclmulqdq::get_update_with(),
+        }
+    }
+
+    /// Computes the guard of the block `data` with its `metadata`.
+    fn guard(&self, data: &[u8], metadata: &[u8]) -> u64 {
+        let covered = &metadata[..self.layout.pi_offset()];
+        match self.layout.format {
+            PiFormat::Guard16 => u64::from(crc16_t10dif(crc16_t10dif(0, data), covered)),
+            PiFormat::Guard32 => u64::from(!crc32c(crc32c(!0, data), covered)),
+            PiFormat::Guard64 => {
+                let state = (self.update)(&table::NVME, !0, data);
+                !(self.update)(&table::NVME, state, covered)
+            }
+        }
+    }
+
+    /// Returns the reference tag of block `index`, starting from `first`.
+    fn ref_tag(&self, first: u64, index: usize) -> u64 {
+        first.wrapping_add(index as u64) & mask(self.layout.ref_tag_bits())
+    }
+
+    fn write(&self, metadata: &mut [u8], pi: &Pi) {
+        let format = self.layout.format;
+        let g = format.guard_size();
+        let pi_bytes = &mut metadata[self.layout.pi_offset()..][..format.size()];
+        let (guard, rest) = pi_bytes.split_at_mut(g);
+        let (app_tag, tag) = rest.split_at_mut(2);
+        guard.copy_from_slice(&pi.guard.to_be_bytes()[8 - g..]);
+        app_tag.copy_from_slice(&pi.app_tag.to_be_bytes());
+        let storage_tag = u128::from(pi.storage_tag & mask(self.layout.storage_tag_bits));
+        let value = storage_tag << self.layout.ref_tag_bits() | u128::from(pi.ref_tag);
+        tag.copy_from_slice(&value.to_be_bytes()[16 - tag.len()..]);
+    }
+
+    fn read(&self, metadata: &[u8]) -> Pi {
+        let format = self.layout.format;
+        let g = format.guard_size();
+        let pi_bytes = &metadata[self.layout.pi_offset()..][..format.size()];
+        let (guard, rest) = pi_bytes.split_at(g);
+        let (app_tag, tag) = rest.split_at(2);
+        let mut bytes = [0; 8];
+        bytes[8 - g..].copy_from_slice(guard);
+        let guard = u64::from_be_bytes(bytes);
+        let mut bytes = [0; 16];
+        bytes[16 - tag.len()..].copy_from_slice(tag);
+        let value = u128::from_be_bytes(bytes);
+        let ref_tag_bits = self.layout.ref_tag_bits();
+        Pi {
+            guard,
+            app_tag: u16::from_be_bytes([app_tag[0], app_tag[1]]),
+            storage_tag: (value >> ref_tag_bits) as u64,
+            ref_tag: value as u64 & mask(ref_tag_bits),
+        }
+    }
+}
+
+fn generate_blocks<'a>(
+    layout: &Layout,
+    blocks: impl Iterator<Item = (&'a [u8], &'a mut [u8])>,
+    tags: Tags,
+) {
+    let codec = Codec::new(layout);
+    for (i, (data, metadata)) in blocks.enumerate() {
+        let pi = Pi {
+            guard: codec.guard(data, metadata),
+            app_tag: tags.app_tag,
+            storage_tag: tags.storage_tag,
+            ref_tag: codec.ref_tag(tags.ref_tag, i),
+        };
+        codec.write(metadata, &pi);
+    }
+}
+
+fn verify_blocks<'a>(
+    layout: &Layout,
+    blocks: impl Iterator<Item = (&'a [u8], &'a [u8])>,
+    expected: Tags,
+    checks: &Checks,
+) -> Result<(), Vec<PiError>> {
+    let codec = Codec::new(layout);
+    let mut errors = vec![];
+    for (block, (data, metadata)) in blocks.enumerate() {
+        let pi = codec.read(metadata);
+        if pi.app_tag == 0xffff {
+            continue;
+        }
+        if checks.guard {
+            let guard = codec.guard(data, metadata);
+            if pi.guard != guard {
+                errors.push(PiError::Guard {
+                    block,
+                    expected: guard,
+                    actual: pi.guard,
+                });
+            }
+        }
+        if (pi.app_tag ^ expected.app_tag) & checks.app_tag_mask != 0 {
+            errors.push(PiError::AppTag {
+                block,
+                expected: expected.app_tag,
+                actual: pi.app_tag,
+            });
+        }
+        let storage_tag = expected.storage_tag & mask(layout.storage_tag_bits);
+        if checks.storage_tag && pi.storage_tag != storage_tag {
+            errors.push(PiError::StorageTag {
+                block,
+                expected: storage_tag,
+                actual: pi.storage_tag,
+            });
+        }
+        let ref_tag = codec.ref_tag(expected.ref_tag, block);
+        if checks.ref_tag && pi.ref_tag != ref_tag {
+            errors.push(PiError::RefTag {
+                block,
+                expected: ref_tag,
+                actual: pi.ref_tag,
+            });
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Returns a mask of the `bits` least significant bits.
+fn mask(bits: u32) -> u64 {
+    u64::MAX.checked_shr(64 - bits).unwrap_or(0)
+}
+
+/// The CRC-16 T10-DIF polynomial, in normal notation.
+const T10DIF_POLY: u16 = 0x8bb7;
+
+/// The slicing-by-8 tables of CRC-16 T10-DIF: `T10DIF_TABLES[k][i]` is the
+/// register after feeding the byte `i` followed by `k` zero bytes.
+static T10DIF_TABLES: [[u16; 256]; 8] = {
+    let mut tables = [[0; 256]; 8];
+    let mut i = 0;
+    while i < 256 {
+        let mut value = (i as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            value = value << 1 ^ if value & 0x8000 != 0 { T10DIF_POLY } else { 0 };
+            bit += 1;
+        }
+        tables[0][i] = value;
+        i += 1;
+    }
+    let mut k = 1;
+    while k < 8 {
+        let mut i = 0;
+        while i < 256 {
+            let prev = tables[k - 1][i];
+            tables[k][i] = prev << 8 ^ tables[0][(prev >> 8) as usize];
+            i += 1;
+        }
+        k += 1;
+    }
+    tables
+};
+
+/// Updates a CRC-16 T10-DIF register, which has no initial value nor final
+/// XOR.
+fn crc16_t10dif(mut state: u16, bytes: &[u8]) -> u16 {
+    let t = &T10DIF_TABLES;
+    let mut chunks = bytes.chunks_exact(8);
+    for chunk in &mut chunks {
+        let s = state.to_be_bytes();
+        state = (0..8).fold(0, |acc, i| {
+            let b = chunk[i] ^ s.get(i).copied().unwrap_or(0);
+            acc ^ t[7 - i][usize::from(b)]
+        });
+    }
+    chunks.remainder().iter().fold(state, |state, b| {
+        state << 8 ^ t[0][usize::from((state >> 8) as u8 ^ b)]
+    })
+}
+
+/// The CRC-32C (Castagnoli) polynomial, in reflected notation.
+const CRC32C_POLY: u32 = 0x82f6_3b78;
+
+/// The slicing-by-8 tables of CRC-32C, like `T10DIF_TABLES`.
+static CRC32C_TABLES: [[u32; 256]; 8] = {
+    let mut tables = [[0; 256]; 8];
+    let mut i = 0;
+    while i < 256 {
+        let mut value = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            value = value >> 1 ^ if value & 1 != 0 { CRC32C_POLY } else { 0 };
+            bit += 1;
+        }
+        tables[0][i] = value;
+        i += 1;
+    }
+    let mut k = 1;
+    while k < 8 {
+        let mut i = 0;
+        while i < 256 {
+            let prev = tables[k - 1][i];
+            tables[k][i] = prev >> 8 ^ tables[0][(prev & 0xff) as usize];
+            i += 1;
+        }
+        k += 1;
+    }
+    tables
+};
+
+/// Updates a CRC-32C register.
+fn crc32c(mut state: u32, bytes: &[u8]) -> u32 {
+    let t = &CRC32C_TABLES;
+    let mut chunks = bytes.chunks_exact(8);
+    for chunk in &mut chunks {
+        let s = state.to_le_bytes();
+        state = (0..8).fold(0, |acc, i| {
+            let b = chunk[i] ^ s.get(i).copied().unwrap_or(0);
+            acc ^ t[7 - i][usize::from(b)]
+        });
+    }
+    chunks.remainder().iter().fold(state, |state, b| {
+        state >> 8 ^ t[0][usize::from(state as u8 ^ b)]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crc::{Crc, CRC_16_T10_DIF, CRC_32_ISCSI, CRC_64_NVME};
+    use proptest::prelude::*;
+
+    const CRC16: Crc<u16> = Crc::<u16>::new(&CRC_16_T10_DIF);
+    const CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISCSI);
+    const CRC64: Crc<u64> = Crc::<u64>::new(&CRC_64_NVME);
+
+    const FORMATS: [PiFormat; 3] = [PiFormat::Guard16, PiFormat::Guard32, PiFormat::Guard64];
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len as u32)
+            .map(|i| (i.wrapping_mul(0x9e37_79b9) >> 24) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn test_guards() {
+        assert_eq!(crc16_t10dif(0, b"123456789"), 0xd0db);
+        assert_eq!(!crc32c(!0, b"123456789"), 0xe306_9283);
+
+        let data = data(4096);
+        for (format, expected) in [
+            (PiFormat::Guard16, u64::from(CRC16.checksum(&data))),
+            (PiFormat::Guard32, u64::from(CRC32.checksum(&data))),
+            (PiFormat::Guard64, CRC64.checksum(&data)),
+        ] {
+            let layout = Layout::new(format, 4096);
+            let metadata = vec![0; format.size()];
+            assert_eq!(Codec::new(&layout).guard(&data, &metadata), expected);
+        }
+    }
+
+    #[test]
+    fn test_known_pi() {
+        let layout = Layout::new(PiFormat::Guard64, 9).with_storage_tag_bits(8);
+        let mut buf = *b"123456789\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
+        let tags = Tags {
+            app_tag: 0xabcd,
+            storage_tag: 0x5a,
+            ref_tag: 0x0102_0304,
+        };
+        generate(&layout, &mut buf, tags);
+        assert_eq!(
+            buf[9..],
+            [
+                0xae, 0x8b, 0x14, 0x86, 0x0a, 0x79, 0x98, 0x88, // guard
+                0xab, 0xcd, // application tag
+                0x5a, 0x00, 0x01, 0x02, 0x03, 0x04, // storage and reference tags
+            ]
+        );
+
+        let layout = Layout::new(PiFormat::Guard16, 9);
+        let mut buf = *b"123456789\0\0\0\0\0\0\0\0";
+        generate(&layout, &mut buf, tags);
+        assert_eq!(buf[9..], [0xd0, 0xdb, 0xab, 0xcd, 0x01, 0x02, 0x03, 0x04]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let layout = Layout::new(PiFormat::Guard32, 512);
+        let ext = layout.extended_block_size();
+        let tags = Tags {
+            app_tag: 7,
+            storage_tag: 0x77,
+            ref_tag: 100,
+        };
+        let mut buf = data(8 * ext);
+        generate(&layout, &mut buf, tags);
+
+        buf[3] ^= 0x10; // data of block 0
+        buf[2 * ext + 512 + 5] ^= 1; // application tag of block 2
+        buf[5 * ext + 512 + 7] ^= 1; // storage tag of block 5
+        buf[6 * ext - 1] ^= 1; // reference tag of block 5
+        let errors = verify(&layout, &buf, tags, &Checks::default()).unwrap_err();
+        let blocks: Vec<usize> = errors
+            .iter()
+            .map(|e| match e {
+                PiError::Guard { block, .. }
+                | PiError::AppTag { block, .. }
+                | PiError::StorageTag { block, .. }
+                | PiError::RefTag { block, .. } => *block,
+            })
+            .collect();
+        assert_eq!(blocks, [0, 2, 5, 5]);
+        assert!(matches!(errors[0], PiError::Guard { .. }));
+        assert_eq!(
+            errors[1],
+            PiError::AppTag {
+                block: 2,
+                expected: 7,
+                actual: 6
+            }
+        );
+        assert_eq!(
+            errors[2],
+            PiError::StorageTag {
+                block: 5,
+                expected: 0x77,
+                actual: 0x76
+            }
+        );
+        assert_eq!(
+            errors[3],
+            PiError::RefTag {
+                block: 5,
+                expected: 105,
+                actual: 104
+            }
+        );
+
+        // disabled checks are not reported.
+        let checks = Checks {
+            guard: false,
+            app_tag_mask: 0xfffe,
+            storage_tag: false,
+            ref_tag: false,
+        };
+        assert_eq!(verify(&layout, &buf, tags, &checks), Ok(()));
+
+        // neither are blocks with the escape application tag.
+        let mut buf = data(ext);
+        generate(&layout, &mut buf, Tags::default());
+        buf[..512].fill(0);
+        buf[512 + 4..512 + 6].fill(0xff);
+        assert_eq!(verify(&layout, &buf, tags, &Checks::default()), Ok(()));
+    }
+
+    proptest! {
+        #[test]
+        fn sliced_guards(bytes in prop::collection::vec(any::<u8>(), 0..100), split in 0..100usize) {
+            let (a, b) = bytes.split_at(split.min(bytes.len()));
+            prop_assert_eq!(crc16_t10dif(crc16_t10dif(0, a), b), CRC16.checksum(&bytes));
+            prop_assert_eq!(!crc32c(crc32c(!0, a), b), CRC32.checksum(&bytes));
+        }
+
+        #[test]
+        fn separate_matches_interleaved(
+            format in prop::sample::select(FORMATS.to_vec()),
+            blocks in 0..8usize,
+            extra in 0..8usize,
+            pi_first: bool,
+            tags in any::<(u16, u64, u64)>(),
+        ) {
+            let mut layout = Layout::new(format, 512).with_metadata_size(format.size() + extra);
+            if pi_first {
+                layout = layout.with_pi_first();
+            }
+            let tags = Tags {
+                app_tag: tags.0 & 0x7fff,
+                storage_tag: tags.1,
+                ref_tag: tags.2,
+            };
+            let ext = layout.extended_block_size();
+            let mut buf = data(blocks * ext);
+            generate(&layout, &mut buf, tags);
+            prop_assert_eq!(verify(&layout, &buf, tags, &Checks::default()), Ok(()));
+
+            let mut data = vec![];
+            let mut metadata = vec![];
+            for block in buf.chunks(ext) {
+                data.extend_from_slice(&block[..512]);
+                metadata.extend_from_slice(&block[512..]);
+            }
+            let mut generated = metadata.clone();
+            generated.iter_mut().for_each(|b| *b ^= 0xff);
+            for (g, m) in generated.chunks_mut(layout.metadata_size()).zip(metadata.chunks(layout.metadata_size())) {
+                // keep the bytes covered by the guard but outside of the PI.
+                let pi = layout.pi_offset()..layout.pi_offset() + format.size();
+                g[..pi.start].copy_from_slice(&m[..pi.start]);
+                g[pi.end..].copy_from_slice(&m[pi.end..]);
+            }
+            generate_separate(&layout, &data, &mut generated, tags);
+            prop_assert_eq!(&generated, &metadata);
+            prop_assert_eq!(verify_separate(&layout, &data, &metadata, tags, &Checks::default()), Ok(()));
+        }
+    }
+}
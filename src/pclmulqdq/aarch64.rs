@@ -2,7 +2,9 @@
 
 //! AArch64 implementation of the PCLMULQDQ-based CRC calculation.
 
-use std::arch::{aarch64::*, is_aarch64_feature_detected};
+use super::SimdExt;
+use crate::Backend;
+use std::arch::{aarch64::*, asm, is_aarch64_feature_detected};
 use std::mem::transmute;
 use std::ops::BitXor;
 
@@ -43,7 +45,9 @@ impl Simd {
     }
 }
 
-impl super::SimdExt for Simd {
+impl SimdExt for Simd {
+    const BACKEND: Backend = Backend::Pmull;
+
     fn is_supported() -> bool {
         is_aarch64_feature_detected!("pmull") && is_aarch64_feature_detected!("neon")
     }
@@ -87,7 +91,92 @@ impl super::SimdExt for Simd {
 impl BitXor for Simd {
     type Output = Simd;
 
+    #[inline]
     fn bitxor(self, other: Self) -> Self {
         unsafe { Self(veorq_u8(self.0, other.0)) }
     }
 }
+
+/// Same as [`Simd`], but merging the products and the data in `fold_16_xor`
+/// with the three-way XOR `eor3` of the SHA3 extension.
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug)]
+pub struct Eor3(Simd);
+
+impl SimdExt for Eor3 {
+    const BACKEND: Backend = Backend::PmullEor3;
+
+    fn is_supported() -> bool {
+        Simd::is_supported() && is_aarch64_feature_detected!("sha3")
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn new(high: u64, low: u64) -> Self {
+        Self(Simd::new(high, low))
+    }
+
+    #[inline]
+    #[target_feature(enable = "aes", enable = "neon")]
+    unsafe fn fold_16(self, coeff: Self) -> Self {
+        Self(self.0.fold_16(coeff.0))
+    }
+
+    #[inline]
+    #[target_feature(enable = "aes", enable = "neon")]
+    unsafe fn fold_16_xor(self, coeff: Self, data: Self) -> Self {
+        let [x0, x1] = self.0.into_poly64s();
+        let [c0, c1] = coeff.0.into_poly64s();
+        let h = Simd::from_mul(c0, x0);
+        let l = Simd::from_mul(c1, x1);
+        let res: uint8x16_t;
+        // `veor3q_u8` is only stable since Rust 1.79, and enabling the
+        // `sha3` target feature here would prevent inlining into the
+        // folding loops, so the extension is enabled in the assembly.
+        asm!(
+            ".arch_extension sha3",
+            "eor3 {res:v}.16b, {h:v}.16b, {l:v}.16b, {data:v}.16b",
+            h = in(vreg) h.0,
+            l = in(vreg) l.0,
+            data = in(vreg) data.0 .0,
+            res = lateout(vreg) res,
+            options(pure, nomem, nostack),
+        );
+        Self(Simd(res))
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn fold_8(self, coeff: u64) -> Self {
+        Self(self.0.fold_8(coeff))
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn barrett(self, poly: u64, mu: u64) -> u64 {
+        self.0.barrett(poly, mu)
+    }
+}
+
+impl BitXor for Eor3 {
+    type Output = Self;
+
+    #[inline]
+    fn bitxor(self, other: Self) -> Self {
+        Self(self.0 ^ other.0)
+    }
+}
+
+#[test]
+fn test_eor3() {
+    if !Eor3::is_supported() {
+        return;
+    }
+    let data: Vec<u8> = (0..1000u32).map(|i| (i * 13) as u8).collect();
+    for len in [0, 128, 255, 256, 511, 512, 999] {
+        assert_eq!(
+            super::update_wide::<Eor3>(!0, &data[..len]),
+            super::table::update(!0, &data[..len]),
+        );
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+//! Reads the ELF auxiliary vector of the current process, and on RISC-V
+//! the `riscv_hwprobe` system call.
+//!
+//! This is used for runtime CPU feature detection on the platforms whose
+//! `is_*_feature_detected!` macros are not available on stable Rust.
+
+/// Returns the value of the auxiliary vector entry `key`, or 0 if it is
+/// missing or the auxiliary vector cannot be read.
+pub fn getauxval(key: usize) -> usize {
+    const SIZE: usize = std::mem::size_of::<usize>();
+
+    let Ok(auxv) = std::fs::read("/proc/self/auxv") else {
+        return 0;
+    };
+    auxv.chunks_exact(2 * SIZE)
+        .map(|entry| {
+            let (key, value) = entry.split_at(SIZE);
+            (
+                usize::from_ne_bytes(key.try_into().unwrap()),
+                usize::from_ne_bytes(value.try_into().unwrap()),
+            )
+        })
+        .find(|(k, _)| *k == key)
+        .map_or(0, |(_, value)| value)
+}
+
+/// Returns the value of the `riscv_hwprobe` key `key`, or 0 if the kernel
+/// does not know it (`riscv_hwprobe` appeared in Linux 6.4).
+#[cfg(target_arch = "riscv64")]
+pub fn riscv_hwprobe(key: i64) -> u64 {
+    /// `__NR_riscv_hwprobe`.
+    const SYS_RISCV_HWPROBE: usize = 258;
+
+    // struct riscv_hwprobe { __s64 key; __u64 value; }
+    let mut pair: [i64; 2] = [key, 0];
+    let ret: isize;
+    // SAFETY: the kernel writes at most the one pair passed in. An empty
+    // CPU set means all the online CPUs.
+    unsafe {
+        std::arch::asm!(
+            "ecall",
+            in("a7") SYS_RISCV_HWPROBE,
+            inlateout("a0") pair.as_mut_ptr() => ret,
+            in("a1") 1usize, // pair_count
+            in("a2") 0usize, // cpusetsize
+            in("a3") 0usize, // cpus
+            in("a4") 0usize, // flags
+            options(nostack),
+        );
+    }
+    // unknown keys are replaced by -1.
+    if ret != 0 || pair[0] != key {
+        return 0;
+    }
+    pair[1] as u64
+}
@@ -10,13 +10,41 @@
 #[cfg(not(feature = "fake-simd"))]
 #[cfg_attr(any(target_arch = "x86", target_arch = "x86_64"), path = "x86.rs")]
 #[cfg_attr(target_arch = "aarch64", path = "aarch64.rs")]
+#[cfg_attr(
+    all(target_arch = "arm", target_os = "linux", target_endian = "little"),
+    path = "arm.rs"
+)]
+#[cfg_attr(
+    all(target_arch = "powerpc64", target_os = "linux", crc64fast_powerpc_asm),
+    path = "powerpc64.rs"
+)]
+#[cfg_attr(
+    all(target_arch = "s390x", target_os = "linux", crc64fast_s390x_asm),
+    path = "s390x.rs"
+)]
+#[cfg_attr(all(target_arch = "riscv64", target_os = "linux"), path = "riscv64.rs")]
 mod arch;
 
 #[cfg(feature = "fake-simd")]
 mod arch;
 
+#[cfg(all(
+    not(feature = "fake-simd"),
+    target_os = "linux",
+    any(
+        all(target_arch = "arm", target_endian = "little"),
+        all(target_arch = "powerpc64", crc64fast_powerpc_asm),
+        target_arch = "riscv64",
+        all(target_arch = "s390x", crc64fast_s390x_asm)
+    )
+))]
+mod auxv;
+
 use self::arch::Simd;
-use super::table;
+use super::table::{self, Params};
+use super::Backend;
+use std::env::{self, VarError};
+use std::sync::OnceLock;
 use std::{
     fmt::Debug,
     ops::{BitXor, BitXorAssign},
@@ -24,11 +52,18 @@ use std::{
 
 /// This trait must be implemented on `self::arch::Simd` to provide the
 /// platform-specific SIMD implementations.
-trait SimdExt: Copy + Debug + BitXor {
+trait SimdExt: Copy + Debug + BitXor<Output = Self> {
+    /// The backend reported for this implementation.
+    const BACKEND: Backend;
+
     /// Returns whether SIMD-accelerated carryless multiplication is supported.
     fn is_supported() -> bool;
 
     /// Creates a new 128-bit integer from the 64-bit parts.
+    ///
+    /// Data blocks are reinterpreted as `Self` straight from memory, so the
+    /// in-memory layout must be the one of a little-endian `u128`, even on
+    /// big-endian hosts: `low` in bytes 0 to 7, then `high` in bytes 8 to 15.
     unsafe fn new(high: u64, low: u64) -> Self;
 
     /// Performs a CRC folding step across 16 bytes.
@@ -37,6 +72,15 @@ trait SimdExt: Copy + Debug + BitXor {
     /// where ⊕ is XOR and ⊗ is carryless multiplication.
     unsafe fn fold_16(self, coeff: Self) -> Self;
 
+    /// Performs a CRC folding step across 16 bytes, and adds `data` to it.
+    ///
+    /// Should return `data ⊕ self.fold_16(coeff)`. Platforms with a
+    /// three-way XOR instruction can override this to save an operation.
+    #[inline(always)]
+    unsafe fn fold_16_xor(self, coeff: Self, data: Self) -> Self {
+        data ^ self.fold_16(coeff)
+    }
+
     /// Performs a CRC folding step across 8 bytes.
     ///
     /// Should return `self.high_64 ⊕ (coeff ⊗ self.low_64)`,
@@ -64,21 +108,365 @@ impl PartialEq for Simd {
 impl Eq for Simd {}
 
 impl BitXorAssign for Simd {
+    #[inline]
     fn bitxor_assign(&mut self, other: Self) {
         *self = *self ^ other;
     }
 }
 
+/// The implementations available on this platform.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Impl {
+    Simd,
+    #[cfg(all(not(feature = "fake-simd"), target_arch = "aarch64"))]
+    Eor3,
+    #[cfg(all(
+        not(feature = "fake-simd"),
+        target_arch = "riscv64",
+        target_os = "linux"
+    ))]
+    Zvbc,
+    Table,
+}
+
+impl Impl {
+    /// Every implementation of this platform, from the fastest one.
+    const ALL: &'static [Self] = &[
+        #[cfg(all(not(feature = "fake-simd"), target_arch = "aarch64"))]
+        Self::Eor3,
+        Self::Simd,
+        #[cfg(all(
+            not(feature = "fake-simd"),
+            target_arch = "riscv64",
+            target_os = "linux"
+        ))]
+        Self::Zvbc,
+        Self::Table,
+    ];
+
+    fn is_supported(self) -> bool {
+        match self {
+            Self::Simd => Simd::is_supported(),
+            #[cfg(all(not(feature = "fake-simd"), target_arch = "aarch64"))]
+            Self::Eor3 => arch::Eor3::is_supported(),
+            #[cfg(all(
+                not(feature = "fake-simd"),
+                target_arch = "riscv64",
+                target_os = "linux"
+            ))]
+            Self::Zvbc => arch::Zvbc::is_supported(),
+            Self::Table => true,
+        }
+    }
+
+    fn backend(self) -> Backend {
+        match self {
+            Self::Simd => Simd::BACKEND,
+            #[cfg(all(not(feature = "fake-simd"), target_arch = "aarch64"))]
+            Self::Eor3 => arch::Eor3::BACKEND,
+            #[cfg(all(
+                not(feature = "fake-simd"),
+                target_arch = "riscv64",
+                target_os = "linux"
+            ))]
+            Self::Zvbc => arch::Zvbc::BACKEND,
+            Self::Table => Backend::Table,
+        }
+    }
+
+    fn update(self) -> super::UpdateFn {
+        match self {
+            Self::Simd => update::<Simd>,
+            #[cfg(all(not(feature = "fake-simd"), target_arch = "aarch64"))]
+            Self::Eor3 => update_wide::<arch::Eor3>,
+            #[cfg(all(
+                not(feature = "fake-simd"),
+                target_arch = "riscv64",
+                target_os = "linux"
+            ))]
+            Self::Zvbc => update::<arch::Zvbc>,
+            Self::Table => table::update,
+        }
+    }
+
+    fn clmul(self) -> fn(u64, u64) -> u128 {
+        match self {
+            Self::Simd => |a, b| unsafe { clmul_simd::<Simd>(a, b) },
+            #[cfg(all(not(feature = "fake-simd"), target_arch = "aarch64"))]
+            Self::Eor3 => |a, b| unsafe { clmul_simd::<arch::Eor3>(a, b) },
+            #[cfg(all(
+                not(feature = "fake-simd"),
+                target_arch = "riscv64",
+                target_os = "linux"
+            ))]
+            Self::Zvbc => |a, b| unsafe { clmul_simd::<arch::Zvbc>(a, b) },
+            Self::Table => super::gf2::poly_mul,
+        }
+    }
+
+    fn update_chunks(self) -> super::UpdateChunksFn {
+        match self {
+            Self::Simd => update_chunks::<Simd>,
+            #[cfg(all(not(feature = "fake-simd"), target_arch = "aarch64"))]
+            Self::Eor3 => update_chunks::<arch::Eor3>,
+            #[cfg(all(
+                not(feature = "fake-simd"),
+                target_arch = "riscv64",
+                target_os = "linux"
+            ))]
+            Self::Zvbc => update_chunks::<arch::Zvbc>,
+            Self::Table => table::update_chunks,
+        }
+    }
+
+    fn copy_update(self) -> super::CopyUpdateFn {
+        match self {
+            Self::Simd => copy_update::<Simd>,
+            #[cfg(all(not(feature = "fake-simd"), target_arch = "aarch64"))]
+            Self::Eor3 => copy_update::<arch::Eor3>,
+            #[cfg(all(
+                not(feature = "fake-simd"),
+                target_arch = "riscv64",
+                target_os = "linux"
+            ))]
+            Self::Zvbc => copy_update::<arch::Zvbc>,
+            Self::Table => table::copy_update,
+        }
+    }
+
+    fn update_many(self) -> super::UpdateManyFn {
+        match self {
+            Self::Simd => update_many::<Simd>,
+            #[cfg(all(not(feature = "fake-simd"), target_arch = "aarch64"))]
+            Self::Eor3 => update_many::<arch::Eor3>,
+            #[cfg(all(
+                not(feature = "fake-simd"),
+                target_arch = "riscv64",
+                target_os = "linux"
+            ))]
+            Self::Zvbc => update_many::<arch::Zvbc>,
+            Self::Table => table::update_many,
+        }
+    }
+
+    fn update_with(self) -> super::UpdateWithFn {
+        match self {
+            Self::Simd => update_unaligned_with::<Simd>,
+            #[cfg(all(not(feature = "fake-simd"), target_arch = "aarch64"))]
+            Self::Eor3 => update_unaligned_with::<arch::Eor3>,
+            #[cfg(all(
+                not(feature = "fake-simd"),
+                target_arch = "riscv64",
+                target_os = "linux"
+            ))]
+            Self::Zvbc => update_unaligned_with::<arch::Zvbc>,
+            Self::Table => table::update_with,
+        }
+    }
+
+    fn update_multi<const N: usize>(self) -> super::UpdateMultiFn<N> {
+        match self {
+            Self::Simd => update_multi::<Simd, N>,
+            #[cfg(all(not(feature = "fake-simd"), target_arch = "aarch64"))]
+            Self::Eor3 => update_multi::<arch::Eor3, N>,
+            #[cfg(all(
+                not(feature = "fake-simd"),
+                target_arch = "riscv64",
+                target_os = "linux"
+            ))]
+            Self::Zvbc => update_multi::<arch::Zvbc, N>,
+            Self::Table => table::update_multi,
+        }
+    }
+}
+
+/// The selection requested by the `CRC64FAST_BACKEND` environment variable.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Requested {
+    /// The fastest supported implementation, also used if the variable is
+    /// not set.
+    Auto,
+    /// The table-based implementation.
+    Table,
+    /// The fastest SIMD implementation, which must be supported.
+    Simd,
+}
+
+impl Requested {
+    /// Parses the value of `CRC64FAST_BACKEND`.
+    fn parse(value: Result<String, VarError>) -> Result<Self, String> {
+        match value.as_deref() {
+            Ok("auto") | Err(VarError::NotPresent) => Ok(Self::Auto),
+            Ok("table") => Ok(Self::Table),
+            Ok("simd") => Ok(Self::Simd),
+            Ok(other) => Err(format!(
+                "invalid CRC64FAST_BACKEND `{}`, expected `table`, `simd` or `auto`",
+                other
+            )),
+            Err(VarError::NotUnicode(other)) => Err(format!(
+                "invalid CRC64FAST_BACKEND {:?}, expected `table`, `simd` or `auto`",
+                other
+            )),
+        }
+    }
+
+    /// Returns the selection of the environment, which is only read once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the variable is set to an invalid value.
+    fn from_env() -> Self {
+        static REQUESTED: OnceLock<Requested> = OnceLock::new();
+        *REQUESTED
+            .get_or_init(|| Self::parse(env::var(ENV_VAR)).unwrap_or_else(|e| panic!("{}", e)))
+    }
+}
+
+/// The environment variable overriding runtime detection.
+const ENV_VAR: &str = "CRC64FAST_BACKEND";
+
+/// Detects the fastest implementation supported by the CPU, honouring the
+/// `CRC64FAST_BACKEND` environment variable.
+///
+/// # Panics
+///
+/// Panics if the variable is invalid, or requests SIMD on a CPU which does
+/// not support it.
+fn detect() -> Impl {
+    let mut impls = Impl::ALL.iter().copied().filter(|i| i.is_supported());
+    match Requested::from_env() {
+        Requested::Auto => impls.next().unwrap_or(Impl::Table),
+        Requested::Table => Impl::Table,
+        Requested::Simd => impls
+            .find(|i| *i != Impl::Table)
+            .unwrap_or_else(|| panic!("{}=simd, but the CPU does not support SIMD", ENV_VAR)),
+    }
+}
+
 pub fn get_update() -> super::UpdateFn {
-    if Simd::is_supported() {
-        update
+    detect().update()
+}
+
+pub fn get_update_chunks() -> super::UpdateChunksFn {
+    detect().update_chunks()
+}
+
+pub fn get_copy_update() -> super::CopyUpdateFn {
+    detect().copy_update()
+}
+
+pub fn get_update_many() -> super::UpdateManyFn {
+    detect().update_many()
+}
+
+pub fn get_update_with() -> super::UpdateWithFn {
+    detect().update_with()
+}
+
+pub fn get_update_multi<const N: usize>() -> super::UpdateMultiFn<N> {
+    detect().update_multi()
+}
+
+/// Returns the backend of the fastest implementation supported by the CPU.
+pub fn detected_backend() -> Backend {
+    detect().backend()
+}
+
+/// Returns the implementation of `backend`, for single and chained buffers,
+/// or `None` if it is not supported by the CPU.
+pub fn get_update_for(backend: Backend) -> Option<(super::UpdateFn, super::UpdateChunksFn)> {
+    let mut impls = Impl::ALL.iter().copied();
+    impls
+        .find(|i| i.backend() == backend && i.is_supported())
+        .map(|i| (i.update(), i.update_chunks()))
+}
+
+/// Whether the target features enabled at compile time guarantee that the
+/// SIMD implementation is supported, making runtime detection unnecessary.
+const STATICALLY_SUPPORTED: bool = cfg!(all(
+    not(feature = "fake-simd"),
+    any(
+        all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            target_feature = "pclmulqdq",
+            target_feature = "sse4.1"
+        ),
+        all(target_arch = "aarch64", target_feature = "aes")
+    )
+));
+
+/// Returns whether the SIMD implementation is supported by the CPU.
+pub fn is_supported() -> bool {
+    STATICALLY_SUPPORTED || Simd::is_supported()
+}
+
+/// Updates the CRC state with the SIMD implementation. Must only be called
+/// if `is_supported()` returned true.
+#[inline]
+pub fn update_simd_only(state: u64, bytes: &[u8]) -> u64 {
+    update::<Simd>(state, bytes)
+}
+
+/// Like `update_simd_only`, but for the concatenation of `chunks`.
+pub fn update_chunks_simd_only(state: u64, chunks: &mut dyn Iterator<Item = &[u8]>) -> u64 {
+    update_chunks::<Simd>(state, chunks)
+}
+
+/// Returns whether the SIMD implementation is known to be supported at
+/// compile time, and not overridden by `CRC64FAST_BACKEND=table`.
+pub fn use_static_simd() -> bool {
+    STATICALLY_SUPPORTED && Requested::from_env() != Requested::Table
+}
+
+/// Updates the CRC state with the SIMD implementation if `static_simd`, as
+/// returned by `use_static_simd()`, and with the one found by runtime
+/// detection otherwise. Detection only runs on the first call.
+#[inline]
+pub fn update_auto(static_simd: bool, state: u64, bytes: &[u8]) -> u64 {
+    if static_simd {
+        update::<Simd>(state, bytes)
+    } else {
+        static UPDATE: OnceLock<super::UpdateFn> = OnceLock::new();
+        (UPDATE.get_or_init(get_update))(state, bytes)
+    }
+}
+
+/// Like `update_auto`, but for the concatenation of `chunks`.
+pub fn update_chunks_auto(
+    static_simd: bool,
+    state: u64,
+    chunks: &mut dyn Iterator<Item = &[u8]>,
+) -> u64 {
+    if static_simd {
+        update_chunks::<Simd>(state, chunks)
     } else {
-        table::update
+        static UPDATE_CHUNKS: OnceLock<super::UpdateChunksFn> = OnceLock::new();
+        (UPDATE_CHUNKS.get_or_init(get_update_chunks))(state, chunks)
     }
 }
 
-fn update(mut state: u64, bytes: &[u8]) -> u64 {
-    let (left, middle, right) = unsafe { bytes.align_to::<[Simd; 8]>() };
+/// Returns the carryless multiplication of two 64-bit polynomials, with the
+/// SIMD instructions if they are supported.
+pub fn get_clmul() -> fn(u64, u64) -> u128 {
+    detect().clmul()
+}
+
+#[cfg_attr(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature(enable = "pclmulqdq", enable = "sse2", enable = "sse4.1")
+)]
+#[cfg_attr(
+    target_arch = "aarch64",
+    target_feature(enable = "aes", enable = "neon")
+)]
+unsafe fn clmul_simd<S: SimdExt>(a: u64, b: u64) -> u128 {
+    let product = S::new(0, a).fold_16(S::new(0, b));
+    let bytes: [u8; 16] = std::mem::transmute_copy(&product);
+    u128::from_le_bytes(bytes)
+}
+
+fn update<S: SimdExt>(mut state: u64, bytes: &[u8]) -> u64 {
+    let (left, middle, right) = unsafe { bytes.align_to::<[S; 8]>() };
     if let Some((first, rest)) = middle.split_first() {
         state = table::update(state, left);
         state = unsafe { update_simd(state, first, rest) };
@@ -88,6 +476,110 @@ fn update(mut state: u64, bytes: &[u8]) -> u64 {
     }
 }
 
+/// Like `update`, but folding 256 bytes per iteration, for platforms with
+/// enough SIMD registers to hold the larger accumulator.
+#[cfg(any(test, all(not(feature = "fake-simd"), target_arch = "aarch64")))]
+fn update_wide<S: SimdExt>(mut state: u64, bytes: &[u8]) -> u64 {
+    let (left, middle, right) = unsafe { bytes.align_to::<[S; 16]>() };
+    if let Some((first, rest)) = middle.split_first() {
+        state = table::update(state, left);
+        state = unsafe { update_simd_wide(state, first, rest) };
+        table::update(state, right)
+    } else {
+        update::<S>(state, bytes)
+    }
+}
+
+/// Like `update`, but for the concatenation of `chunks`.
+fn update_chunks<S: SimdExt>(state: u64, chunks: &mut dyn Iterator<Item = &[u8]>) -> u64 {
+    unsafe { update_simd_chunks::<S>(state, chunks) }
+}
+
+/// Like `update`, but also copies `src` into `dst`, which must have the
+/// same length.
+fn copy_update<S: SimdExt>(mut state: u64, dst: &mut [u8], src: &[u8]) -> u64 {
+    let (left, middle, right) = unsafe { src.align_to::<[S; 8]>() };
+    if let Some((first, rest)) = middle.split_first() {
+        let (dst_left, dst) = dst.split_at_mut(left.len());
+        let (dst_middle, dst_right) = dst.split_at_mut(std::mem::size_of_val(middle));
+        state = table::copy_update(state, dst_left, left);
+        state = unsafe { update_simd_copy(state, dst_middle.as_mut_ptr().cast(), first, rest) };
+        table::copy_update(state, dst_right, right)
+    } else {
+        table::copy_update(state, dst, src)
+    }
+}
+
+/// The lengths for which `update_multi` folds one polynomial at a time.
+///
+/// Shorter data is bound by the latency of the folds, which a single pass
+/// over all polynomials overlaps. Longer data is bound by reading memory, so
+/// it is read only once. In between, the data stays in the cache across the
+/// passes, and each pass keeps the accumulators of a single polynomial in
+/// the SIMD registers instead of spilling those of all of them.
+const MULTI_PASS_LENGTHS: std::ops::Range<usize> = 2048..4 << 20;
+
+/// Updates the CRC state of every polynomial over `bytes`.
+fn update_multi<S: SimdExt, const N: usize>(
+    states: &mut [u64; N],
+    params: &[&'static Params; N],
+    bytes: &[u8],
+) {
+    if bytes.len() < 16 || MULTI_PASS_LENGTHS.contains(&bytes.len()) {
+        for (state, p) in states.iter_mut().zip(params) {
+            *state = update_unaligned_with::<S>(p, *state, bytes);
+        }
+    } else {
+        unsafe { update_simd_multi::<S, N>(states, params, bytes) }
+    }
+}
+
+/// Like `update`, but reading `bytes` unaligned, which is faster for a whole
+/// short message.
+fn update_unaligned<S: SimdExt>(state: u64, bytes: &[u8]) -> u64 {
+    update_unaligned_with::<S>(&table::ECMA, state, bytes)
+}
+
+/// Like `update_unaligned`, but for the polynomial of `p`.
+fn update_unaligned_with<S: SimdExt>(p: &Params, state: u64, bytes: &[u8]) -> u64 {
+    if bytes.len() < 16 {
+        table::update_with(p, state, bytes)
+    } else {
+        unsafe { update_simd_unaligned::<S>(p, state, bytes) }
+    }
+}
+
+/// The number of messages `update_many` folds side by side, as many as the
+/// accumulators `update_simd` folds a long message with. Fewer lanes leave
+/// blocks of a few hundred bytes slower than one `update` each.
+const LANES: usize = 8;
+
+/// Updates the CRC state of each message, folding them `LANES` at a time.
+/// A batch with a message under 16 bytes, and the messages left over after
+/// the last batch, are updated one by one.
+fn update_many<S: SimdExt>(states: &mut [u64], messages: &[&[u8]]) {
+    let len = states.len().min(messages.len());
+    let (states, messages) = (&mut states[..len], &messages[..len]);
+    let mut states = states.chunks_exact_mut(LANES);
+    let mut batches = messages.chunks_exact(LANES);
+    for (states, batch) in (&mut states).zip(&mut batches) {
+        if batch.iter().all(|bytes| bytes.len() >= 16) {
+            let states = states.try_into().unwrap();
+            unsafe { update_simd_lanes::<S>(states, batch.try_into().unwrap()) };
+        } else {
+            update_one_by_one::<S>(states, batch);
+        }
+    }
+    update_one_by_one::<S>(states.into_remainder(), batches.remainder());
+}
+
+/// Updates the CRC state of each message in turn.
+fn update_one_by_one<S: SimdExt>(states: &mut [u64], messages: &[&[u8]]) {
+    for (state, bytes) in states.iter_mut().zip(messages) {
+        *state = update_unaligned::<S>(*state, bytes);
+    }
+}
+
 #[cfg_attr(
     any(target_arch = "x86", target_arch = "x86_64"),
     target_feature(enable = "pclmulqdq", enable = "sse2", enable = "sse4.1")
@@ -96,35 +588,423 @@ fn update(mut state: u64, bytes: &[u8]) -> u64 {
     target_arch = "aarch64",
     target_feature(enable = "aes", enable = "neon")
 )]
-unsafe fn update_simd(state: u64, first: &[Simd; 8], rest: &[[Simd; 8]]) -> u64 {
+unsafe fn update_simd<S: SimdExt>(state: u64, first: &[S; 8], rest: &[[S; 8]]) -> u64 {
     // receive the initial 128 bytes of data
     let mut x = *first;
 
     // xor the initial CRC value
-    x[0] ^= Simd::new(0, state);
+    x[0] = x[0] ^ S::new(0, state);
 
     // perform 128-byte folding.
-    let coeff = Simd::new(table::K_1023, table::K_1087);
+    let coeff = S::new(table::K_1023, table::K_1087);
     for chunk in rest {
         for (xi, yi) in x.iter_mut().zip(chunk.iter()) {
-            *xi = *yi ^ xi.fold_16(coeff);
+            *xi = xi.fold_16_xor(coeff, *yi);
         }
     }
 
+    reduce_128(x)
+}
+
+/// Like `update_simd`, but also stores every block to `dst`, which may be
+/// unaligned and must be valid for writing as many blocks as read.
+#[cfg_attr(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature(enable = "pclmulqdq", enable = "sse2", enable = "sse4.1")
+)]
+#[cfg_attr(
+    target_arch = "aarch64",
+    target_feature(enable = "aes", enable = "neon")
+)]
+unsafe fn update_simd_copy<S: SimdExt>(
+    state: u64,
+    dst: *mut [S; 8],
+    first: &[S; 8],
+    rest: &[[S; 8]],
+) -> u64 {
+    // receive the initial 128 bytes of data, and store them as is.
+    let mut x = *first;
+    dst.write_unaligned(x);
+
+    // xor the initial CRC value
+    x[0] = x[0] ^ S::new(0, state);
+
+    // perform 128-byte folding, storing each block once loaded.
+    let coeff = S::new(table::K_1023, table::K_1087);
+    for (i, chunk) in rest.iter().enumerate() {
+        let chunk = *chunk;
+        dst.add(i + 1).write_unaligned(chunk);
+        for (xi, yi) in x.iter_mut().zip(chunk.iter()) {
+            *xi = xi.fold_16_xor(coeff, *yi);
+        }
+    }
+
+    reduce_128(x)
+}
+
+/// Like `update_simd`, but for the concatenation of `chunks`, which may be
+/// of any length and alignment.
+///
+/// Blocks are read unaligned, and the bytes of a block straddling chunks
+/// are gathered in a buffer, so that folding goes on across the chunk
+/// boundaries. Only the bytes after the last whole block go through the
+/// table.
+#[cfg_attr(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature(enable = "pclmulqdq", enable = "sse2", enable = "sse4.1")
+)]
+#[cfg_attr(
+    target_arch = "aarch64",
+    target_feature(enable = "aes", enable = "neon")
+)]
+unsafe fn update_simd_chunks<S: SimdExt>(
+    state: u64,
+    chunks: &mut dyn Iterator<Item = &[u8]>,
+) -> u64 {
+    // the 128-byte accumulator, once the first block has been received.
+    let mut x = None;
+    // the bytes not folded yet, always fewer than 128.
+    let mut pending = [0; 128];
+    let mut len = 0;
+
+    for mut bytes in chunks {
+        if len > 0 {
+            let n = bytes.len().min(128 - len);
+            pending[len..len + n].copy_from_slice(&bytes[..n]);
+            bytes = &bytes[n..];
+            len += n;
+            if len < 128 {
+                continue;
+            }
+            fold_128(
+                &mut x,
+                state,
+                pending.as_ptr().cast::<[S; 8]>().read_unaligned(),
+            );
+        }
+        let blocks = bytes.chunks_exact(128);
+        let rest = blocks.remainder();
+        for block in blocks {
+            fold_128(
+                &mut x,
+                state,
+                block.as_ptr().cast::<[S; 8]>().read_unaligned(),
+            );
+        }
+        pending[..rest.len()].copy_from_slice(rest);
+        len = rest.len();
+    }
+
+    let state = match x {
+        Some(x) => reduce_128(x),
+        None => state,
+    };
+    table::update(state, &pending[..len])
+}
+
+/// Folds the 128-byte accumulator `x` over the next block, or initializes
+/// it with the block and the initial CRC value `state`.
+#[inline(always)]
+unsafe fn fold_128<S: SimdExt>(x: &mut Option<[S; 8]>, state: u64, block: [S; 8]) {
+    match x {
+        Some(x) => {
+            let coeff = S::new(table::K_1023, table::K_1087);
+            for (xi, yi) in x.iter_mut().zip(block.iter()) {
+                *xi = xi.fold_16_xor(coeff, *yi);
+            }
+        }
+        None => {
+            let mut block = block;
+            block[0] = block[0] ^ S::new(0, state);
+            *x = Some(block);
+        }
+    }
+}
+
+/// Reduces the 128-byte accumulator `x` into the final CRC state.
+#[inline(always)]
+unsafe fn reduce_128<S: SimdExt>(x: [S; 8]) -> u64 {
+    reduce_16(fold_128_into_16(x, &table::ECMA), &table::ECMA)
+}
+
+/// Folds the 128-byte accumulator `x` into a 16-byte one, using the
+/// constants of `p`.
+#[inline(always)]
+unsafe fn fold_128_into_16<S: SimdExt>(x: [S; 8], p: &Params) -> S {
     let coeffs = [
-        Simd::new(table::K_895, table::K_959), // fold by distance of 112 bytes
-        Simd::new(table::K_767, table::K_831), // fold by distance of 96 bytes
-        Simd::new(table::K_639, table::K_703), // fold by distance of 80 bytes
-        Simd::new(table::K_511, table::K_575), // fold by distance of 64 bytes
-        Simd::new(table::K_383, table::K_447), // fold by distance of 48 bytes
-        Simd::new(table::K_255, table::K_319), // fold by distance of 32 bytes
-        Simd::new(table::K_127, table::K_191), // fold by distance of 16 bytes
+        S::new(p.k_895, p.k_959), // fold by distance of 112 bytes
+        S::new(p.k_767, p.k_831), // fold by distance of 96 bytes
+        S::new(p.k_639, p.k_703), // fold by distance of 80 bytes
+        S::new(p.k_511, p.k_575), // fold by distance of 64 bytes
+        S::new(p.k_383, p.k_447), // fold by distance of 48 bytes
+        S::new(p.k_255, p.k_319), // fold by distance of 32 bytes
+        S::new(p.k_127, p.k_191), // fold by distance of 16 bytes
     ];
     x.iter()
         .zip(&coeffs)
-        .fold(x[7], |acc, (m, c)| acc ^ m.fold_16(*c))
-        .fold_8(table::K_127) // finally fold 16 bytes into 8 bytes.
-        .barrett(table::POLY, table::MU) // barrett reduction.
+        .fold(x[7], |acc, (m, c)| m.fold_16_xor(*c, acc))
+}
+
+/// Reduces the 16-byte accumulator `x` into the final CRC state, using the
+/// constants of `p`.
+#[inline(always)]
+unsafe fn reduce_16<S: SimdExt>(x: S, p: &Params) -> u64 {
+    x.fold_8(p.k_127) // fold 16 bytes into 8 bytes.
+        .barrett(p.poly, p.mu) // barrett reduction.
+}
+
+/// Folds the 16-byte accumulator `x` over the last `len` bytes of the
+/// message, where `0 < len < 16`. `last` holds the last 16 bytes of the
+/// message, which overlap the bytes already folded into `x`.
+#[inline(always)]
+unsafe fn fold_tail<S: SimdExt>(x: S, last: S, len: usize, p: &Params) -> S {
+    let to_u128 = |s: S| u128::from_le_bytes(std::mem::transmute_copy(&s));
+    let from_u128 = |v: u128| std::mem::transmute_copy::<[u8; 16], S>(&v.to_le_bytes());
+
+    // `x ‖ tail` is `head ‖ body`, with the first `len` bytes of `x` in
+    // `head` and the last 16 bytes of the message in `body`. Padding `head`
+    // with leading zeros does not change its value, so it is folded by a
+    // distance of 16 bytes.
+    let (x, last) = (to_u128(x), to_u128(last));
+    let shift = 8 * (16 - len);
+    let head = x << shift;
+    let body = x >> (8 * len) | last >> shift << shift;
+    from_u128(head).fold_16_xor(S::new(p.k_127, p.k_191), from_u128(body))
+}
+
+/// Like `update_simd`, but with a 256-byte accumulator.
+#[cfg(any(test, all(not(feature = "fake-simd"), target_arch = "aarch64")))]
+#[cfg_attr(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature(enable = "pclmulqdq", enable = "sse2", enable = "sse4.1")
+)]
+#[cfg_attr(
+    target_arch = "aarch64",
+    target_feature(enable = "aes", enable = "neon")
+)]
+unsafe fn update_simd_wide<S: SimdExt>(state: u64, first: &[S; 16], rest: &[[S; 16]]) -> u64 {
+    // receive the initial 256 bytes of data
+    let mut x = *first;
+
+    // xor the initial CRC value
+    x[0] = x[0] ^ S::new(0, state);
+
+    // perform 256-byte folding.
+    let coeff = S::new(table::K_2047, table::K_2111);
+    for chunk in rest {
+        for (xi, yi) in x.iter_mut().zip(chunk.iter()) {
+            *xi = xi.fold_16_xor(coeff, *yi);
+        }
+    }
+
+    // fold the first half into the second one, by distance of 128 bytes.
+    let coeff = S::new(table::K_1023, table::K_1087);
+    let mut y = [S::new(0, 0); 8];
+    for (yi, (xi, xj)) in y.iter_mut().zip(x[..8].iter().zip(&x[8..])) {
+        *yi = xi.fold_16_xor(coeff, *xj);
+    }
+    reduce_128(y)
+}
+
+/// Like `update_simd`, but for a whole message of at least 16 bytes, of
+/// any alignment, and for the polynomial of `p`.
+///
+/// Blocks are read unaligned, and the bytes after the last whole 16-byte
+/// block are folded with an overlapping read, so that none of the message
+/// goes through the table. This keeps short messages, whose aligned middle
+/// would be a small part of them, on the SIMD path.
+#[cfg_attr(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature(enable = "pclmulqdq", enable = "sse2", enable = "sse4.1")
+)]
+#[cfg_attr(
+    target_arch = "aarch64",
+    target_feature(enable = "aes", enable = "neon")
+)]
+unsafe fn update_simd_unaligned<S: SimdExt>(p: &Params, state: u64, bytes: &[u8]) -> u64 {
+    debug_assert!(bytes.len() >= 16);
+    let read = |pos: usize| bytes.as_ptr().add(pos).cast::<S>().read_unaligned();
+
+    let mut blocks = bytes.chunks_exact(128);
+    let rest = blocks.remainder();
+    let mut x = if let Some(first) = blocks.next() {
+        // receive the initial 128 bytes of data, and xor the initial CRC
+        // value.
+        let mut x = first.as_ptr().cast::<[S; 8]>().read_unaligned();
+        x[0] = x[0] ^ S::new(0, state);
+
+        // perform 128-byte folding, then fold the accumulator into 16 bytes.
+        let coeff = S::new(p.k_1023, p.k_1087);
+        for block in blocks {
+            let block = block.as_ptr().cast::<[S; 8]>().read_unaligned();
+            for (xi, yi) in x.iter_mut().zip(block.iter()) {
+                *xi = xi.fold_16_xor(coeff, *yi);
+            }
+        }
+        fold_128_into_16(x, p)
+    } else {
+        read(0) ^ S::new(0, state)
+    };
+
+    // perform 16-byte folding over the remaining whole blocks.
+    let coeff = S::new(p.k_127, p.k_191);
+    let start = bytes.len() - rest.len();
+    let mut pos = if start == 0 { 16 } else { start };
+    while pos + 16 <= bytes.len() {
+        x = x.fold_16_xor(coeff, read(pos));
+        pos += 16;
+    }
+
+    if pos < bytes.len() {
+        x = fold_tail(x, read(bytes.len() - 16), bytes.len() - pos, p);
+    }
+    reduce_16(x, p)
+}
+
+/// Like `update_simd_unaligned`, but for the `N` polynomials of `params` at
+/// once.
+///
+/// Each block is loaded once, then folded into one accumulator per
+/// polynomial, with the constants of that polynomial. This needs `N` times
+/// the carryless multiplications of a single polynomial, but only one pass
+/// over memory.
+#[cfg_attr(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature(enable = "pclmulqdq", enable = "sse2", enable = "sse4.1")
+)]
+#[cfg_attr(
+    target_arch = "aarch64",
+    target_feature(enable = "aes", enable = "neon")
+)]
+unsafe fn update_simd_multi<S: SimdExt, const N: usize>(
+    states: &mut [u64; N],
+    params: &[&'static Params; N],
+    bytes: &[u8],
+) {
+    debug_assert!(bytes.len() >= 16);
+    let read = |pos: usize| bytes.as_ptr().add(pos).cast::<S>().read_unaligned();
+
+    let mut blocks = bytes.chunks_exact(128);
+    let rest = blocks.remainder();
+    let mut x: [S; N] = if let Some(first) = blocks.next() {
+        // receive the initial 128 bytes of data, and xor the initial CRC
+        // value of each polynomial.
+        let first = first.as_ptr().cast::<[S; 8]>().read_unaligned();
+        let mut x: [[S; 8]; N] = std::array::from_fn(|i| {
+            let mut x = first;
+            x[0] = x[0] ^ S::new(0, states[i]);
+            x
+        });
+
+        // perform 128-byte folding, then fold each accumulator into 16 bytes.
+        let coeffs: [S; N] = std::array::from_fn(|i| S::new(params[i].k_1023, params[i].k_1087));
+        for block in blocks {
+            let block = block.as_ptr().cast::<[S; 8]>().read_unaligned();
+            for (x, coeff) in x.iter_mut().zip(&coeffs) {
+                for (xi, yi) in x.iter_mut().zip(block.iter()) {
+                    *xi = xi.fold_16_xor(*coeff, *yi);
+                }
+            }
+        }
+        std::array::from_fn(|i| fold_128_into_16(x[i], params[i]))
+    } else {
+        let first = read(0);
+        std::array::from_fn(|i| first ^ S::new(0, states[i]))
+    };
+
+    // perform 16-byte folding over the remaining whole blocks.
+    let coeffs: [S; N] = std::array::from_fn(|i| S::new(params[i].k_127, params[i].k_191));
+    let start = bytes.len() - rest.len();
+    let mut pos = if start == 0 { 16 } else { start };
+    while pos + 16 <= bytes.len() {
+        let block = read(pos);
+        for (x, coeff) in x.iter_mut().zip(&coeffs) {
+            *x = x.fold_16_xor(*coeff, block);
+        }
+        pos += 16;
+    }
+
+    if pos < bytes.len() {
+        let last = read(bytes.len() - 16);
+        for (x, p) in x.iter_mut().zip(params) {
+            *x = fold_tail(*x, last, bytes.len() - pos, p);
+        }
+    }
+    for ((state, x), p) in states.iter_mut().zip(x).zip(params) {
+        *state = reduce_16(x, p);
+    }
+}
+
+/// Like `update_simd_unaligned`, but for `LANES` messages of at least 16
+/// bytes each, folded side by side with one 16-byte accumulator per message.
+///
+/// Each fold of a message depends on the previous one, so a single short
+/// message is bound by the latency of the carryless multiplications rather
+/// than by their throughput. Interleaving independent messages keeps one
+/// multiplication per message in flight, and the final reductions overlap.
+#[cfg_attr(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature(enable = "pclmulqdq", enable = "sse2", enable = "sse4.1")
+)]
+#[cfg_attr(
+    target_arch = "aarch64",
+    target_feature(enable = "aes", enable = "neon")
+)]
+unsafe fn update_simd_lanes<S: SimdExt>(states: &mut [u64; LANES], messages: &[&[u8]; LANES]) {
+    debug_assert!(messages.iter().all(|bytes| bytes.len() >= 16));
+    let p = &table::ECMA;
+    let read = |lane: usize, pos: usize| {
+        messages[lane]
+            .as_ptr()
+            .add(pos)
+            .cast::<S>()
+            .read_unaligned()
+    };
+
+    // receive the initial 16 bytes of each message, and xor its initial CRC
+    // value.
+    let mut x: [S; LANES] = std::array::from_fn(|lane| read(lane, 0) ^ S::new(0, states[lane]));
+
+    // perform 16-byte folding over the blocks every message has.
+    let coeff = S::new(p.k_127, p.k_191);
+    let shortest = messages.iter().map(|bytes| bytes.len()).min().unwrap_or(0);
+    let mut pos = 16;
+    while pos + 16 <= shortest {
+        for (lane, xi) in x.iter_mut().enumerate() {
+            *xi = xi.fold_16_xor(coeff, read(lane, pos));
+        }
+        pos += 16;
+    }
+
+    // finish each message on its own, then reduce them all.
+    for (lane, xi) in x.iter_mut().enumerate() {
+        let len = messages[lane].len();
+        let mut pos = pos;
+        while pos + 16 <= len {
+            *xi = xi.fold_16_xor(coeff, read(lane, pos));
+            pos += 16;
+        }
+        if pos < len {
+            *xi = fold_tail(*xi, read(lane, len - 16), len - pos, p);
+        }
+    }
+    for (state, xi) in states.iter_mut().zip(x) {
+        *state = reduce_16(xi, p);
+    }
+}
+
+#[test]
+fn test_requested_parse() {
+    let parse = |value: &str| Requested::parse(Ok(value.to_owned()));
+    assert_eq!(
+        Requested::parse(Err(VarError::NotPresent)),
+        Ok(Requested::Auto)
+    );
+    assert_eq!(parse("auto"), Ok(Requested::Auto));
+    assert_eq!(parse("table"), Ok(Requested::Table));
+    assert_eq!(parse("simd"), Ok(Requested::Simd));
+    assert!(parse("").is_err());
+    assert!(parse("SIMD").is_err());
 }
 
 #[test]
@@ -144,6 +1024,21 @@ fn test_new() {
     }
 }
 
+#[test]
+fn test_memory_layout() {
+    let bytes: [u8; 16] = [
+        0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee,
+        0xff,
+    ];
+    unsafe {
+        let loaded: Simd = std::mem::transmute(bytes);
+        let expected = Simd::new(0xffee_ddcc_bbaa_9988, 0x7766_5544_3322_1100);
+        assert_eq!(loaded, expected);
+        let bytes_out: [u8; 16] = std::mem::transmute(expected);
+        assert_eq!(bytes_out, bytes);
+    }
+}
+
 #[test]
 fn test_xor() {
     unsafe {
@@ -182,3 +1077,72 @@ fn test_barrett() {
         assert_eq!(b, 0x5e4d_0253_942a_d95d);
     }
 }
+
+#[test]
+fn test_update_matches_table() {
+    if !Simd::is_supported() {
+        return;
+    }
+    let data: Vec<u8> = (0..4200u32)
+        .map(|i| (i.wrapping_mul(0x9e37_79b9) >> 24) as u8)
+        .collect();
+    for start in 0..16 {
+        for len in [
+            0, 1, 15, 16, 17, 47, 127, 128, 129, 255, 256, 257, 511, 512, 1000, 4096,
+        ] {
+            let bytes = &data[start..start + len];
+            let expected = table::update(!0, bytes);
+            assert_eq!(update::<Simd>(!0, bytes), expected, "{}+{}", start, len);
+            for split in [0, 1, 100, 127, 128, 200] {
+                let split = split.min(len);
+                let (a, b) = bytes.split_at(split);
+                let mut chunks = [a, &[], b].into_iter();
+                assert_eq!(
+                    update_chunks::<Simd>(!0, &mut chunks),
+                    expected,
+                    "{}+{}/{}",
+                    start,
+                    len,
+                    split
+                );
+            }
+            let mut states = [!0];
+            update_many::<Simd>(&mut states, &[bytes]);
+            assert_eq!(states[0], expected, "{}+{}", start, len);
+            // every other lane one byte shorter, or shorter than a block.
+            let messages: Vec<&[u8]> = (0..2 * LANES + 1)
+                .map(|i| &bytes[..len.saturating_sub(i % 2 + i / LANES * 16)])
+                .collect();
+            let mut states = vec![!0; messages.len()];
+            update_many::<Simd>(&mut states, &messages);
+            for (state, bytes) in states.iter().zip(&messages) {
+                assert_eq!(
+                    *state,
+                    table::update(!0, bytes),
+                    "{}+{}",
+                    start,
+                    bytes.len()
+                );
+            }
+            let mut states = [!0; 2];
+            update_multi::<Simd, 2>(&mut states, &[&table::ECMA, &table::NVME], bytes);
+            assert_eq!(
+                states,
+                [expected, table::update_with(&table::NVME, !0, bytes)],
+                "{}+{}",
+                start,
+                len
+            );
+            let mut dst = vec![0; len + 1];
+            assert_eq!(copy_update::<Simd>(!0, &mut dst[1..], bytes), expected);
+            assert_eq!(&dst[1..], bytes);
+            assert_eq!(
+                update_wide::<Simd>(!0, bytes),
+                expected,
+                "{}+{}",
+                start,
+                len
+            );
+        }
+    }
+}
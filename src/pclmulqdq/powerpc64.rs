@@ -0,0 +1,124 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+//! PowerPC64 implementation of the PCLMULQDQ-based CRC calculation.
+//!
+//! POWER8 (ISA 2.07) introduced `vpmsumd`, which multiplies both 64-bit
+//! halves of two vectors and XORs the products together, i.e. exactly a
+//! `fold_16` step. Vector types are not available to inline assembly on
+//! stable Rust for PowerPC, so values live in general purpose registers and
+//! are moved into vector registers around each multiplication.
+//!
+//! `asm!` is stable on PowerPC since Rust 1.95, so older compilers build the
+//! table-based implementation only (see `build.rs`).
+
+use super::auxv::getauxval;
+use super::SimdExt;
+use crate::Backend;
+use std::arch::asm;
+use std::ops::BitXor;
+use std::sync::OnceLock;
+
+/// A 128-bit value, laid out as the 16 bytes it is loaded from: the low 64
+/// bits first, both halves in little-endian byte order. This keeps the
+/// layout the same on ppc64 and ppc64le.
+#[repr(C, align(16))]
+#[derive(Copy, Clone, Debug)]
+pub struct Simd([u64; 2]);
+
+impl Simd {
+    #[inline]
+    fn high_64(self) -> u64 {
+        u64::from_le(self.0[1])
+    }
+
+    #[inline]
+    fn low_64(self) -> u64 {
+        u64::from_le(self.0[0])
+    }
+
+    /// Computes `(a.high_64 ⊗ b.high_64) ⊕ (a.low_64 ⊗ b.low_64)`.
+    #[inline]
+    unsafe fn vpmsumd(a: Self, b: Self) -> Self {
+        let (high, low): (u64, u64);
+        // VSX registers 32 to 34 are the vector registers v0 to v2. The ISA
+        // numbers doublewords from the most significant one, so doubleword 0
+        // holds the high half. The default target CPU predates POWER8, hence
+        // the `.machine` directives.
+        asm!(
+            ".machine push",
+            ".machine power8",
+            "mtvsrd 32, {ah}",
+            "mtvsrd 33, {al}",
+            "xxpermdi 32, 32, 33, 0",
+            "mtvsrd 33, {bh}",
+            "mtvsrd 34, {bl}",
+            "xxpermdi 33, 33, 34, 0",
+            "vpmsumd 0, 0, 1",
+            "mfvsrd {high}, 32",
+            "xxpermdi 33, 32, 32, 2",
+            "mfvsrd {low}, 33",
+            ".machine pop",
+            ah = in(reg) a.high_64(),
+            al = in(reg) a.low_64(),
+            bh = in(reg) b.high_64(),
+            bl = in(reg) b.low_64(),
+            high = lateout(reg) high,
+            low = lateout(reg) low,
+            out("v0") _,
+            out("v1") _,
+            out("v2") _,
+            options(pure, nomem, nostack),
+        );
+        Self::new(high, low)
+    }
+
+    #[inline]
+    unsafe fn from_mul(a: u64, b: u64) -> Self {
+        Self::vpmsumd(Self::new(0, a), Self::new(0, b))
+    }
+}
+
+impl SimdExt for Simd {
+    const BACKEND: Backend = Backend::Vpmsumd;
+
+    fn is_supported() -> bool {
+        static SUPPORTED: OnceLock<bool> = OnceLock::new();
+        *SUPPORTED.get_or_init(|| getauxval(AT_HWCAP2) & PPC_FEATURE2_ARCH_2_07 != 0)
+    }
+
+    #[inline]
+    unsafe fn new(high: u64, low: u64) -> Self {
+        Self([low.to_le(), high.to_le()])
+    }
+
+    #[inline]
+    unsafe fn fold_16(self, coeff: Self) -> Self {
+        Self::vpmsumd(self, coeff)
+    }
+
+    #[inline]
+    unsafe fn fold_8(self, coeff: u64) -> Self {
+        let h = Self::from_mul(coeff, self.low_64());
+        let l = Self::new(0, self.high_64());
+        h ^ l
+    }
+
+    #[inline]
+    unsafe fn barrett(self, poly: u64, mu: u64) -> u64 {
+        let t1 = Self::from_mul(self.low_64(), mu).low_64();
+        let l = Self::from_mul(t1, poly);
+        (self ^ l).high_64() ^ t1
+    }
+}
+
+impl BitXor for Simd {
+    type Output = Self;
+
+    #[inline]
+    fn bitxor(self, other: Self) -> Self {
+        Self([self.0[0] ^ other.0[0], self.0[1] ^ other.0[1]])
+    }
+}
+
+const AT_HWCAP2: usize = 26;
+const PPC_FEATURE2_ARCH_2_07: usize = 0x8000_0000;
@@ -0,0 +1,205 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+//! RISC-V implementation of the PCLMULQDQ-based CRC calculation.
+//!
+//! Two implementations are provided: [`Simd`] uses the scalar `clmul` and
+//! `clmulh` instructions of the Zbc extension, and [`Zvbc`] uses their
+//! vector counterparts `vclmul` and `vclmulh` for CPUs which only implement
+//! the vector crypto extensions. The extensions are enabled locally in the
+//! inline assembly, since the default target does not include them.
+//!
+//! `is_riscv_feature_detected!` is not stable, so the extensions are
+//! detected from the auxiliary vector and the `riscv_hwprobe` system call.
+
+use super::auxv::{getauxval, riscv_hwprobe};
+use super::SimdExt;
+use crate::Backend;
+use std::arch::asm;
+use std::ops::BitXor;
+use std::sync::OnceLock;
+
+const AT_HWCAP: usize = 16;
+/// The bit of the single-letter extension V in `AT_HWCAP`.
+const HWCAP_V: usize = 1 << (b'V' - b'A');
+
+const RISCV_HWPROBE_KEY_IMA_EXT_0: i64 = 4;
+const RISCV_HWPROBE_EXT_ZBC: u64 = 1 << 7;
+const RISCV_HWPROBE_EXT_ZVBC: u64 = 1 << 18;
+
+/// Returns the extensions of `RISCV_HWPROBE_KEY_IMA_EXT_0` supported by all
+/// the CPUs, which are only probed once.
+fn ima_ext_0() -> u64 {
+    static EXT: OnceLock<u64> = OnceLock::new();
+    *EXT.get_or_init(|| riscv_hwprobe(RISCV_HWPROBE_KEY_IMA_EXT_0))
+}
+
+/// A 128-bit value, stored as `[low_64, high_64]`.
+#[repr(C, align(16))]
+#[derive(Copy, Clone, Debug)]
+pub struct Simd([u64; 2]);
+
+/// Computes the 128-bit carryless product of `a` and `b` with Zbc, returned
+/// as `(high, low)`.
+#[inline]
+unsafe fn clmul(a: u64, b: u64) -> (u64, u64) {
+    let (high, low): (u64, u64);
+    asm!(
+        ".option push",
+        ".option arch, +zbc",
+        "clmulh {high}, {a}, {b}",
+        "clmul {low}, {a}, {b}",
+        ".option pop",
+        a = in(reg) a,
+        b = in(reg) b,
+        high = out(reg) high,
+        low = lateout(reg) low,
+        options(pure, nomem, nostack),
+    );
+    (high, low)
+}
+
+impl SimdExt for Simd {
+    const BACKEND: Backend = Backend::Zbc;
+
+    fn is_supported() -> bool {
+        ima_ext_0() & RISCV_HWPROBE_EXT_ZBC != 0
+    }
+
+    #[inline]
+    unsafe fn new(high: u64, low: u64) -> Self {
+        Self([low, high])
+    }
+
+    #[inline]
+    unsafe fn fold_16(self, coeff: Self) -> Self {
+        let (h1, l1) = clmul(self.0[1], coeff.0[1]);
+        let (h0, l0) = clmul(self.0[0], coeff.0[0]);
+        Self([l0 ^ l1, h0 ^ h1])
+    }
+
+    #[inline]
+    unsafe fn fold_8(self, coeff: u64) -> Self {
+        let (h, l) = clmul(coeff, self.0[0]);
+        Self([l ^ self.0[1], h])
+    }
+
+    #[inline]
+    unsafe fn barrett(self, poly: u64, mu: u64) -> u64 {
+        let (_, t1) = clmul(self.0[0], mu);
+        let (h, _) = clmul(t1, poly);
+        self.0[1] ^ h ^ t1
+    }
+}
+
+impl BitXor for Simd {
+    type Output = Self;
+
+    #[inline]
+    fn bitxor(self, other: Self) -> Self {
+        Self([self.0[0] ^ other.0[0], self.0[1] ^ other.0[1]])
+    }
+}
+
+/// Same as [`Simd`], but multiplying with the Zvbc vector extension.
+#[repr(C, align(16))]
+#[derive(Copy, Clone, Debug)]
+pub struct Zvbc([u64; 2]);
+
+impl Zvbc {
+    /// Computes `(a.high_64 ⊗ b.high_64) ⊕ (a.low_64 ⊗ b.low_64)`.
+    #[inline]
+    unsafe fn vclmul(a: Self, b: Self) -> Self {
+        let (high, low): (u64, u64);
+        // Each operand is loaded as a 2-element vector of 64-bit integers.
+        // Both products are computed at once, and their halves summed with
+        // XOR reductions.
+        asm!(
+            ".option push",
+            ".option arch, +v, +zvbc",
+            "vsetivli zero, 2, e64, m1, ta, ma",
+            "vmv.v.x v1, {al}",
+            "vslide1down.vx v2, v1, {ah}",
+            "vmv.v.x v1, {bl}",
+            "vslide1down.vx v3, v1, {bh}",
+            "vclmul.vv v4, v2, v3",
+            "vclmulh.vv v5, v2, v3",
+            "vmv.v.i v1, 0",
+            "vredxor.vs v4, v4, v1",
+            "vredxor.vs v5, v5, v1",
+            "vmv.x.s {low}, v4",
+            "vmv.x.s {high}, v5",
+            ".option pop",
+            al = in(reg) a.0[0],
+            ah = in(reg) a.0[1],
+            bl = in(reg) b.0[0],
+            bh = in(reg) b.0[1],
+            high = lateout(reg) high,
+            low = lateout(reg) low,
+            out("v1") _,
+            out("v2") _,
+            out("v3") _,
+            out("v4") _,
+            out("v5") _,
+            options(pure, nomem, nostack),
+        );
+        Self([low, high])
+    }
+}
+
+impl SimdExt for Zvbc {
+    const BACKEND: Backend = Backend::Zvbc;
+
+    fn is_supported() -> bool {
+        // Zvbc only requires Zve64x, which allows 64-bit vectors. V
+        // guarantees the 128-bit vectors needed to hold both halves.
+        static V: OnceLock<bool> = OnceLock::new();
+        *V.get_or_init(|| getauxval(AT_HWCAP) & HWCAP_V != 0)
+            && ima_ext_0() & RISCV_HWPROBE_EXT_ZVBC != 0
+    }
+
+    #[inline]
+    unsafe fn new(high: u64, low: u64) -> Self {
+        Self([low, high])
+    }
+
+    #[inline]
+    unsafe fn fold_16(self, coeff: Self) -> Self {
+        Self::vclmul(self, coeff)
+    }
+
+    #[inline]
+    unsafe fn fold_8(self, coeff: u64) -> Self {
+        let h = Self::vclmul(Self::new(0, coeff), Self::new(0, self.0[0]));
+        h ^ Self::new(0, self.0[1])
+    }
+
+    #[inline]
+    unsafe fn barrett(self, poly: u64, mu: u64) -> u64 {
+        let t1 = Self::vclmul(Self::new(0, self.0[0]), Self::new(0, mu)).0[0];
+        let l = Self::vclmul(Self::new(0, t1), Self::new(0, poly));
+        (self ^ l).0[1] ^ t1
+    }
+}
+
+impl BitXor for Zvbc {
+    type Output = Self;
+
+    #[inline]
+    fn bitxor(self, other: Self) -> Self {
+        Self([self.0[0] ^ other.0[0], self.0[1] ^ other.0[1]])
+    }
+}
+
+#[test]
+fn test_zvbc() {
+    if !Zvbc::is_supported() {
+        return;
+    }
+    let data: Vec<u8> = (0..1000u32).map(|i| (i * 13) as u8).collect();
+    for len in [0, 128, 255, 256, 999] {
+        assert_eq!(
+            super::update::<Zvbc>(!0, &data[..len]),
+            super::table::update(!0, &data[..len]),
+        );
+    }
+}
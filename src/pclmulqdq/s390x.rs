@@ -0,0 +1,118 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+//! s390x implementation of the PCLMULQDQ-based CRC calculation.
+//!
+//! The vector facility of z13 and later provides `vgfmg` (Galois field
+//! multiply sum), which multiplies both 64-bit halves of two vectors and
+//! XORs the products together, i.e. exactly a `fold_16` step. Vector types
+//! are not available to inline assembly on stable Rust for s390x, so values
+//! live in general purpose registers and are moved into vector registers
+//! around each multiplication.
+//!
+//! `asm!` is stable on s390x since Rust 1.84, so older compilers build the
+//! table-based implementation only (see `build.rs`).
+
+use super::auxv::getauxval;
+use super::SimdExt;
+use crate::Backend;
+use std::arch::asm;
+use std::ops::BitXor;
+use std::sync::OnceLock;
+
+/// A 128-bit value, laid out as the 16 bytes it is loaded from: the low 64
+/// bits first, both halves in little-endian byte order. s390x is big-endian,
+/// so the halves are byte-swapped when moved to and from registers.
+#[repr(C, align(16))]
+#[derive(Copy, Clone, Debug)]
+pub struct Simd([u64; 2]);
+
+impl Simd {
+    #[inline]
+    fn high_64(self) -> u64 {
+        u64::from_le(self.0[1])
+    }
+
+    #[inline]
+    fn low_64(self) -> u64 {
+        u64::from_le(self.0[0])
+    }
+
+    /// Computes `(a.high_64 ⊗ b.high_64) ⊕ (a.low_64 ⊗ b.low_64)`.
+    #[inline]
+    unsafe fn vgfmg(a: Self, b: Self) -> Self {
+        let (high, low): (u64, u64);
+        // Vector elements are numbered from the most significant one, so
+        // element 0 holds the high half. The default target CPU predates the
+        // vector facility, hence the `.machine` directives.
+        asm!(
+            ".machine push",
+            ".machine z13",
+            "vlvgp %v0, {ah}, {al}",
+            "vlvgp %v1, {bh}, {bl}",
+            "vgfmg %v0, %v0, %v1",
+            "vlgvg {high}, %v0, 0",
+            "vlgvg {low}, %v0, 1",
+            ".machine pop",
+            ah = in(reg) a.high_64(),
+            al = in(reg) a.low_64(),
+            bh = in(reg) b.high_64(),
+            bl = in(reg) b.low_64(),
+            high = lateout(reg) high,
+            low = lateout(reg) low,
+            out("v0") _,
+            out("v1") _,
+            options(pure, nomem, nostack),
+        );
+        Self::new(high, low)
+    }
+
+    #[inline]
+    unsafe fn from_mul(a: u64, b: u64) -> Self {
+        Self::vgfmg(Self::new(0, a), Self::new(0, b))
+    }
+}
+
+impl SimdExt for Simd {
+    const BACKEND: Backend = Backend::Vgfm;
+
+    fn is_supported() -> bool {
+        static SUPPORTED: OnceLock<bool> = OnceLock::new();
+        *SUPPORTED.get_or_init(|| getauxval(AT_HWCAP) & HWCAP_S390_VXRS != 0)
+    }
+
+    #[inline]
+    unsafe fn new(high: u64, low: u64) -> Self {
+        Self([low.to_le(), high.to_le()])
+    }
+
+    #[inline]
+    unsafe fn fold_16(self, coeff: Self) -> Self {
+        Self::vgfmg(self, coeff)
+    }
+
+    #[inline]
+    unsafe fn fold_8(self, coeff: u64) -> Self {
+        let h = Self::from_mul(coeff, self.low_64());
+        let l = Self::new(0, self.high_64());
+        h ^ l
+    }
+
+    #[inline]
+    unsafe fn barrett(self, poly: u64, mu: u64) -> u64 {
+        let t1 = Self::from_mul(self.low_64(), mu).low_64();
+        let l = Self::from_mul(t1, poly);
+        (self ^ l).high_64() ^ t1
+    }
+}
+
+impl BitXor for Simd {
+    type Output = Self;
+
+    #[inline]
+    fn bitxor(self, other: Self) -> Self {
+        Self([self.0[0] ^ other.0[0], self.0[1] ^ other.0[1]])
+    }
+}
+
+const AT_HWCAP: usize = 16;
+const HWCAP_S390_VXRS: usize = 1 << 11;
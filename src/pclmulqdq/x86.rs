@@ -2,6 +2,7 @@
 
 //! x86/x86_64 implementation of the PCLMULQDQ-based CRC calculation.
 
+use crate::Backend;
 #[cfg(target_arch = "x86")]
 use std::arch::x86::*;
 #[cfg(target_arch = "x86_64")]
@@ -12,11 +13,33 @@ use std::ops::BitXor;
 #[derive(Copy, Clone, Debug)]
 pub struct Simd(__m128i);
 
+impl Simd {
+    #[cfg(target_arch = "x86_64")]
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn high_64(self) -> u64 {
+        _mm_extract_epi64(self.0, 1) as u64
+    }
+
+    /// 32-bit x86 has no 64-bit general purpose registers, so the high half
+    /// is extracted as two 32-bit lanes.
+    #[cfg(target_arch = "x86")]
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn high_64(self) -> u64 {
+        let low = _mm_extract_epi32(self.0, 2) as u32;
+        let high = _mm_extract_epi32(self.0, 3) as u32;
+        (u64::from(high) << 32) | u64::from(low)
+    }
+}
+
 impl super::SimdExt for Simd {
+    const BACKEND: Backend = Backend::Pclmulqdq;
+
     fn is_supported() -> bool {
         is_x86_feature_detected!("pclmulqdq") // _mm_clmulepi64_si128
             && is_x86_feature_detected!("sse2") // (all other _mm_*)
-            && is_x86_feature_detected!("sse4.1") // _mm_extract_epi64
+            && is_x86_feature_detected!("sse4.1") // _mm_extract_epi64, _mm_extract_epi32
     }
 
     #[inline]
@@ -50,13 +73,14 @@ impl super::SimdExt for Simd {
         let h = Self(_mm_slli_si128(t1, 8));
         let l = Self(_mm_clmulepi64_si128(t1, polymu.0, 0x10));
         let reduced = h ^ l ^ self;
-        _mm_extract_epi64(reduced.0, 1) as u64
+        reduced.high_64()
     }
 }
 
 impl BitXor for Simd {
     type Output = Self;
 
+    #[inline]
     fn bitxor(self, other: Self) -> Self {
         Self(unsafe { _mm_xor_si128(self.0, other.0) })
     }
@@ -0,0 +1,426 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+//! CRC-64 parameter reverse engineering.
+//!
+//! Given sample messages and their checksums, recovers the parameters of
+//! the unknown CRC in the [Rocksoft model] (`poly`, `init`, `refin`,
+//! `refout` and `xorout`), the same way the [`reveng`] tool does.
+//!
+//! The checksums of two messages of equal length differ by
+//! `(M₁ + M₂)·x⁶⁴ mod P`, which cancels out `init` and `xorout`. Hence P
+//! divides `(M₁ + M₂)·x⁶⁴ + (C₁ + C₂)`, and taking the GCD over several such
+//! pairs recovers P. With P known, a pair of messages of *different*
+//! lengths then determines `init`, and any message determines `xorout`.
+//!
+//! At least two distinct messages of the same length, and one message of a
+//! different length, are needed.
+//!
+//! [Rocksoft model]: http://www.ross.net/crc/download/crc_v3.txt
+//! [`reveng`]: https://reveng.sourceforge.io/
+
+use super::gf2::{self, reflect, Poly, Poly64};
+use super::table::{build_tables, update_tables};
+use std::collections::HashMap;
+use std::fmt;
+
+/// The parameters of a 64-bit CRC in the Rocksoft model.
+///
+/// The register shifts towards the most significant bit, and `poly` and
+/// `init` are given in normal (unreflected) notation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CrcParams {
+    /// The generator polynomial, omitting the x⁶⁴ term.
+    pub poly: u64,
+    /// The register value before any data is processed.
+    pub init: u64,
+    /// Whether each input byte is bit-reflected before being processed.
+    pub refin: bool,
+    /// Whether the register is bit-reflected before `xorout` is applied.
+    pub refout: bool,
+    /// The value XORed into the final register.
+    pub xorout: u64,
+}
+
+impl CrcParams {
+    /// The CRC-64/XZ (a.k.a. CRC-64/GO-ECMA) parameters computed by
+    /// [`Digest`](crate::Digest).
+    pub const XZ: Self = Self {
+        poly: 0x42f0_e1eb_a9ea_3693,
+        init: !0,
+        refin: true,
+        refout: true,
+        xorout: !0,
+    };
+
+    /// Computes the checksum of `bytes` using these parameters.
+    ///
+    /// The lookup tables of `poly` are rebuilt on every call, so this is slow
+    /// for short messages.
+    pub fn checksum(&self, bytes: &[u8]) -> u64 {
+        self.checksum_with(&Tables::new(self.poly), bytes)
+    }
+
+    /// Computes the checksum of `bytes`, given the tables of `poly`.
+    fn checksum_with(&self, tables: &Tables, bytes: &[u8]) -> u64 {
+        let reg = tables.register(self.init, self.refin, bytes);
+        let reg = if self.refout { reg.reverse_bits() } else { reg };
+        reg ^ self.xorout
+    }
+}
+
+impl fmt::Display for CrcParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "width=64 poly={:#018x} init={:#018x} refin={} refout={} xorout={:#018x} check={:#018x}",
+            self.poly,
+            self.init,
+            self.refin,
+            self.refout,
+            self.xorout,
+            self.checksum(b"123456789"),
+        )
+    }
+}
+
+/// Error returned by [`reveng`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RevengError {
+    /// The samples do not contain two distinct messages of the same length.
+    TooFewSamples,
+    /// The samples are consistent with some parameters, but do not pin them
+    /// down. Supplying more samples, of varied lengths, may help.
+    Underdetermined,
+    /// No 64-bit CRC produces all the samples.
+    NotFound,
+}
+
+impl fmt::Display for RevengError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::TooFewSamples => "need two distinct messages of the same length",
+            Self::Underdetermined => "samples do not determine the parameters uniquely",
+            Self::NotFound => "no 64-bit CRC matches the samples",
+        })
+    }
+}
+
+impl std::error::Error for RevengError {}
+
+/// The largest degree of an extra common factor which is searched for when
+/// the samples do not isolate the polynomial by themselves.
+const MAX_COFACTOR_DEGREE: usize = 16;
+
+/// The largest number of `init` bits left undetermined by the samples for
+/// which every candidate is still tried.
+const MAX_FREE_INIT_BITS: u32 = 8;
+
+/// Recovers the parameters of the 64-bit CRC which produced `samples`, a
+/// list of `(message, checksum)` pairs.
+///
+/// Returns every parameter set consistent with all samples. There can be
+/// more than one: for instance, when P is divisible by x + 1, some bits of
+/// `init` cannot be told apart by byte-aligned messages at all.
+pub fn reveng(samples: &[(&[u8], u64)]) -> Result<Vec<CrcParams>, RevengError> {
+    let mut found = Vec::new();
+    let mut error = RevengError::NotFound;
+    for (refin, refout) in [(false, false), (true, true), (false, true), (true, false)] {
+        match solve(samples, refin, refout) {
+            Ok(params) => found.extend(params),
+            Err(RevengError::NotFound) => {}
+            Err(e) => error = e,
+        }
+    }
+    if found.is_empty() {
+        Err(error)
+    } else {
+        Ok(found)
+    }
+}
+
+/// Recovers the parameters given the reflection settings.
+fn solve(
+    samples: &[(&[u8], u64)],
+    refin: bool,
+    refout: bool,
+) -> Result<Vec<CrcParams>, RevengError> {
+    let normalize = |c: u64| if refout { c.reverse_bits() } else { c };
+
+    let mut by_len = HashMap::<usize, (&[u8], u64)>::new();
+    let mut gcd = Poly::default();
+    for (msg, crc) in samples {
+        let (base_msg, base_crc) = *by_len.entry(msg.len()).or_insert((msg, *crc));
+        let mut d = message_poly(msg, refin);
+        d.xor_shifted(&message_poly(base_msg, refin), 0);
+        d.xor_shifted(&Poly(vec![normalize(crc ^ base_crc)]), 0);
+        gcd = gcd.gcd(d);
+    }
+
+    // The GCD is P times whatever factors the differences happen to share.
+    // When the extra factor is small, try every polynomial of its degree.
+    let extra = match gcd.degree() {
+        None => return Err(RevengError::TooFewSamples),
+        Some(d) if d < 64 => return Err(RevengError::NotFound),
+        Some(d) if d - 64 > MAX_COFACTOR_DEGREE => return Err(RevengError::Underdetermined),
+        Some(d) => d - 64,
+    };
+    let gcd = gcd.to_u128().expect("degree below 128");
+    let mut found = Vec::new();
+    let mut error = RevengError::NotFound;
+    for low in 0..1u128 << extra {
+        let (poly, rem) = gf2::div_rem(gcd, 1 << extra | low);
+        if rem != 0 {
+            continue;
+        }
+        // The quotient has degree 64, and its x⁶⁴ term is implied.
+        match solve_poly(samples, &by_len, poly as u64, refin, refout) {
+            Ok(params) => found.extend(params),
+            Err(RevengError::NotFound) => {}
+            Err(e) => error = e,
+        }
+    }
+    if found.is_empty() {
+        Err(error)
+    } else {
+        Ok(found)
+    }
+}
+
+/// Recovers the parameters given the polynomial and reflection settings.
+fn solve_poly(
+    samples: &[(&[u8], u64)],
+    by_len: &HashMap<usize, (&[u8], u64)>,
+    poly: u64,
+    refin: bool,
+    refout: bool,
+) -> Result<Vec<CrcParams>, RevengError> {
+    // Undo the output reflection, so that `c = reg ^ xorout'`, where
+    // `xorout'` is `xorout` reflected as needed.
+    let normalize = |c: u64| if refout { c.reverse_bits() } else { c };
+
+    // reg(init, M) = reg(0, M) + init·xᴺ, so for two messages of different
+    // lengths, init·(xᴺ¹ + xᴺ²) = C₁ + C₂ + reg(0, M₁) + reg(0, M₂). The
+    // factor need not be invertible mod P, so collect these as linear
+    // equations over the bits of init and solve them together.
+    let p = Poly64::new(poly);
+    let tables = Tables::new(poly);
+    let mut lens = by_len.values();
+    let (msg_1, crc_1) = *lens.next().ok_or(RevengError::TooFewSamples)?;
+    let mut equations = Vec::new();
+    for (msg_2, crc_2) in lens {
+        let a = p.pow_mod(msg_1.len() as u64 * 8) ^ p.pow_mod(msg_2.len() as u64 * 8);
+        let rhs = normalize(crc_1 ^ crc_2)
+            ^ tables.register(0, refin, msg_1)
+            ^ tables.register(0, refin, msg_2);
+        let columns: Vec<u64> = (0..64).map(|j| p.mul_mod(a, 1 << j)).collect();
+        for i in 0..64 {
+            let row = (0..64).fold(0, |row, j| row | (columns[j] >> i & 1) << j);
+            equations.push((row, rhs >> i & 1 != 0));
+        }
+    }
+    let (init, free) = solve_linear(equations).ok_or(RevengError::NotFound)?;
+    if free.len() as u32 > MAX_FREE_INIT_BITS {
+        return Err(RevengError::Underdetermined);
+    }
+
+    let found: Vec<CrcParams> = (0..1u32 << free.len())
+        .filter_map(|choice| {
+            let init = free
+                .iter()
+                .enumerate()
+                .filter(|(k, _)| choice >> k & 1 != 0)
+                .fold(init, |init, (_, v)| init ^ v);
+            let xorout = normalize(normalize(crc_1) ^ tables.register(init, refin, msg_1));
+            let params = CrcParams {
+                poly,
+                init,
+                refin,
+                refout,
+                xorout,
+            };
+            let matches = samples
+                .iter()
+                .all(|(msg, crc)| params.checksum_with(&tables, msg) == *crc);
+            matches.then_some(params)
+        })
+        .collect();
+    if found.is_empty() {
+        Err(RevengError::NotFound)
+    } else {
+        Ok(found)
+    }
+}
+
+/// Solves a system of linear equations over 64 bits, where each equation
+/// `(row, rhs)` states that the parity of `x & row` is `rhs`.
+///
+/// Returns a particular solution and a basis of the null space, or `None`
+/// if the system is inconsistent.
+fn solve_linear(mut rows: Vec<(u64, bool)>) -> Option<(u64, Vec<u64>)> {
+    // Reduce to row echelon form, remembering which row holds each pivot.
+    let mut pivots = [None; 64];
+    let mut rank = 0;
+    for (col, pivot) in pivots.iter_mut().enumerate() {
+        let Some(r) = (rank..rows.len()).find(|r| rows[*r].0 >> col & 1 != 0) else {
+            continue;
+        };
+        rows.swap(rank, r);
+        let (row, rhs) = rows[rank];
+        for (i, other) in rows.iter_mut().enumerate() {
+            if i != rank && other.0 >> col & 1 != 0 {
+                other.0 ^= row;
+                other.1 ^= rhs;
+            }
+        }
+        *pivot = Some(rank);
+        rank += 1;
+    }
+    if rows[rank..].iter().any(|(_, rhs)| *rhs) {
+        return None;
+    }
+
+    let mut solution = 0;
+    let mut null_space = Vec::new();
+    for (col, pivot) in pivots.iter().enumerate() {
+        match pivot {
+            Some(r) => solution |= u64::from(rows[*r].1) << col,
+            None => {
+                let v = pivots
+                    .iter()
+                    .enumerate()
+                    .fold(1 << col, |v, (c, p)| match p {
+                        Some(r) => v | (rows[*r].0 >> col & 1) << c,
+                        None => v,
+                    });
+                null_space.push(v);
+            }
+        }
+    }
+    Some((solution, null_space))
+}
+
+/// The lookup tables of a polynomial.
+struct Tables(Box<[[u64; 256]; 16]>);
+
+impl Tables {
+    fn new(poly: u64) -> Self {
+        Self(Box::new(build_tables(poly)))
+    }
+
+    /// Computes the register value after processing `bytes`, without output
+    /// reflection or XOR.
+    fn register(&self, init: u64, refin: bool, bytes: &[u8]) -> u64 {
+        // The tables shift a reflected register, consuming the least
+        // significant bit of each byte first, which is what `refin` asks for.
+        let state = reflect(init);
+        let state = if refin {
+            update_tables(&self.0, state, bytes)
+        } else {
+            let bytes: Vec<u8> = bytes.iter().map(|b| b.reverse_bits()).collect();
+            update_tables(&self.0, state, &bytes)
+        };
+        reflect(state)
+    }
+}
+
+/// Converts a message into the polynomial `M·x⁶⁴`, where the first bit
+/// processed is the highest-degree term of M.
+fn message_poly(bytes: &[u8], refin: bool) -> Poly {
+    let mut words = vec![0; bytes.len() / 8 + 2];
+    for (i, b) in bytes.iter().rev().enumerate() {
+        let b = if refin { b.reverse_bits() } else { *b };
+        words[1 + i / 8] |= u64::from(b) << (i % 8 * 8);
+    }
+    Poly(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Digest;
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+    const WE: CrcParams = CrcParams {
+        poly: 0x42f0_e1eb_a9ea_3693,
+        init: !0,
+        refin: false,
+        refout: false,
+        xorout: !0,
+    };
+
+    const GO_ISO: CrcParams = CrcParams {
+        poly: 0x1b,
+        init: !0,
+        refin: true,
+        refout: true,
+        xorout: !0,
+    };
+
+    const ODD: CrcParams = CrcParams {
+        poly: 0xad93_d235_94c9_3659,
+        init: 0x0123_4567_89ab_cdef,
+        refin: false,
+        refout: true,
+        xorout: 0x5555_0000_aaaa_ffff,
+    };
+
+    fn samples(params: &CrcParams, lens: &[usize]) -> Vec<(Vec<u8>, u64)> {
+        let mut rng = StdRng::seed_from_u64(0x5eed);
+        lens.iter()
+            .map(|len| {
+                let mut msg = vec![0; *len];
+                rng.fill_bytes(&mut msg);
+                let crc = params.checksum(&msg);
+                (msg, crc)
+            })
+            .collect()
+    }
+
+    fn run(samples: &[(Vec<u8>, u64)]) -> Result<Vec<CrcParams>, RevengError> {
+        let samples: Vec<(&[u8], u64)> = samples.iter().map(|(m, c)| (&**m, *c)).collect();
+        reveng(&samples)
+    }
+
+    #[test]
+    fn test_check_values() {
+        assert_eq!(CrcParams::XZ.checksum(b"123456789"), 0x995d_c9bb_df19_39fa);
+        assert_eq!(WE.checksum(b"123456789"), 0x62ec_59e3_f1a4_f00a);
+        assert_eq!(GO_ISO.checksum(b"123456789"), 0xb909_56c7_75a4_1001);
+
+        let mut digest = Digest::new();
+        digest.write(b"hello world!");
+        assert_eq!(CrcParams::XZ.checksum(b"hello world!"), digest.sum64());
+    }
+
+    #[test]
+    fn test_reveng() {
+        for params in [CrcParams::XZ, WE, GO_ISO, ODD] {
+            // Some polynomials, like ECMA, leave part of `init` undetermined
+            // for byte-aligned messages, so several answers can be correct.
+            let samples = samples(&params, &[16, 16, 16, 16, 23, 40]);
+            let found = run(&samples).unwrap();
+            assert!(found.contains(&params), "{}", params);
+            for p in found {
+                for (msg, crc) in &samples {
+                    assert_eq!(p.checksum(msg), *crc);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_insufficient_samples() {
+        let distinct_lens = samples(&CrcParams::XZ, &[16, 23, 40]);
+        assert_eq!(run(&distinct_lens), Err(RevengError::TooFewSamples));
+
+        let same_len = samples(&CrcParams::XZ, &[16, 16, 16, 16]);
+        assert_eq!(run(&same_len), Err(RevengError::Underdetermined));
+    }
+
+    #[test]
+    fn test_not_found() {
+        let mut samples = samples(&CrcParams::XZ, &[16, 16, 16, 16, 23, 40]);
+        samples[5].1 ^= 1;
+        assert_eq!(run(&samples), Err(RevengError::NotFound));
+    }
+}
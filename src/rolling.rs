@@ -0,0 +1,320 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+//! Rolling CRC-64-ECMA over a sliding window, and content-defined chunking.
+//!
+//! The CRC register is linear in the message, so the contribution of the
+//! byte leaving a `w`-byte window only depends on its value: it is the
+//! register of that byte followed by `w` zero bytes. [`RollingCrc64`]
+//! precomputes these 256 values, and slides the window by one byte with two
+//! table lookups.
+//!
+//! The window is tracked with a zero initial register, and the initial value
+//! and final XOR of CRC-64-ECMA, which only depend on the window length, are
+//! applied by [`RollingCrc64::sum64`].
+//!
+//! ```
+//! use crc64fast::rolling::RollingCrc64;
+//!
+//! let data = b"xx123456789";
+//! let mut rolling = RollingCrc64::new(9);
+//! rolling.fill(&data[..9]);
+//! rolling.roll(data[0], data[9]);
+//! rolling.roll(data[1], data[10]);
+//! assert_eq!(rolling.sum64(), 0x995d_c9bb_df19_39fa);
+//! ```
+
+use super::table;
+use std::mem;
+
+/// A CRC-64-ECMA over the last `window` bytes of a stream.
+#[derive(Clone, Debug)]
+pub struct RollingCrc64 {
+    window: usize,
+    /// The CRC register of the window, starting from zero.
+    state: u64,
+    /// `out_table[b]` is the register of `b` followed by `window` zeros.
+    out_table: Box<[u64; 256]>,
+    /// The register of `window` zeros, starting from the initial value.
+    init_term: u64,
+}
+
+impl RollingCrc64 {
+    /// Creates a rolling CRC over windows of `window` bytes, initially all
+    /// zeros.
+    ///
+    /// Setting it up takes O(`window`) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is 0.
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0, "window must not be empty");
+        let zeros = vec![0; window];
+        let advance = |state| table::update(state, &zeros);
+
+        // The table is linear in `b`, so only the single-bit entries need
+        // to be advanced over the window.
+        let mut out_table = Box::new([0; 256]);
+        for bit in 0..8 {
+            let b = 1 << bit;
+            out_table[b] = advance(table::update_1(0, b as u8));
+        }
+        for b in 1..256 {
+            let low = b & (b - 1);
+            if low != 0 {
+                out_table[b] = out_table[low] ^ out_table[b ^ low];
+            }
+        }
+
+        Self {
+            window,
+            state: 0,
+            out_table,
+            init_term: advance(!0),
+        }
+    }
+
+    /// Returns the length of the window.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Resets the window to all zeros.
+    pub fn reset(&mut self) {
+        self.state = 0;
+    }
+
+    /// Replaces the window with `bytes`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `bytes` is not the window length.
+    pub fn fill(&mut self, bytes: &[u8]) {
+        assert_eq!(bytes.len(), self.window, "length must match the window");
+        self.state = table::update(0, bytes);
+    }
+
+    /// Slides the window by one byte: `out_byte`, written `window` bytes
+    /// ago, leaves it and `in_byte` enters it.
+    #[inline]
+    pub fn roll(&mut self, out_byte: u8, in_byte: u8) {
+        self.state = table::update_1(self.state, in_byte) ^ self.out_table[usize::from(out_byte)];
+    }
+
+    /// Computes the CRC-64-ECMA value of the window.
+    #[inline]
+    pub fn sum64(&self) -> u64 {
+        !(self.state ^ self.init_term)
+    }
+}
+
+/// Splits a stream into chunks, at positions where the rolling CRC-64 of the
+/// preceding bytes matches a mask.
+///
+/// A chunk ends after a byte when the [`RollingCrc64`] of the last `window`
+/// bytes of the chunk has all the bits of `mask` cleared, so chunks are
+/// `window` bytes long at least, and `mask + 1` bytes long on average for a
+/// mask of contiguous low bits. The boundaries only depend on the nearby
+/// content, so inserting data in a stream only changes the chunks around it.
+///
+/// ```
+/// use crc64fast::rolling::Chunker;
+///
+/// let data: Vec<u8> = (0..100_000u32).map(|i| (i.wrapping_mul(i) >> 7) as u8).collect();
+/// let mut chunker = Chunker::new(48, 0xfff);
+/// let chunks: Vec<&[u8]> = chunker.split(&data).collect();
+/// assert_eq!(chunks.concat(), data);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Chunker {
+    rolling: RollingCrc64,
+    mask: u64,
+    max_size: usize,
+    /// The bytes of the window, as a ring buffer starting at `pos`.
+    ring: Box<[u8]>,
+    pos: usize,
+    /// The length of the current chunk so far.
+    len: usize,
+}
+
+impl Chunker {
+    /// Creates a chunker over windows of `window` bytes, cutting where the
+    /// rolling CRC-64 ANDed with `mask` is zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is 0.
+    pub fn new(window: usize, mask: u64) -> Self {
+        Self {
+            rolling: RollingCrc64::new(window),
+            mask,
+            max_size: usize::MAX,
+            ring: vec![0; window].into_boxed_slice(),
+            pos: 0,
+            len: 0,
+        }
+    }
+
+    /// Also cuts chunks reaching `max_size` bytes, whatever their content.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_size` is 0.
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        assert!(max_size > 0, "chunks must not be empty");
+        self.max_size = max_size;
+        self
+    }
+
+    /// Starts a new chunk, forgetting the data seen so far.
+    pub fn reset(&mut self) {
+        self.rolling.reset();
+        self.ring.fill(0);
+        self.pos = 0;
+        self.len = 0;
+    }
+
+    /// Feeds the next bytes of the stream, and returns the length of the
+    /// prefix of `data` which ends the current chunk, if any.
+    ///
+    /// The bytes after the returned length are not consumed, and should be
+    /// passed again. If no boundary is found, all of `data` is consumed.
+    pub fn find_boundary(&mut self, data: &[u8]) -> Option<usize> {
+        let window = self.ring.len();
+        for (i, &b) in data.iter().enumerate() {
+            let out = mem::replace(&mut self.ring[self.pos], b);
+            self.pos += 1;
+            if self.pos == window {
+                self.pos = 0;
+            }
+            self.rolling.roll(out, b);
+            self.len += 1;
+            if (self.len >= window && self.rolling.sum64() & self.mask == 0)
+                || self.len == self.max_size
+            {
+                self.reset();
+                return Some(i + 1);
+            }
+        }
+        None
+    }
+
+    /// Splits the whole stream `data` into chunks. The last chunk ends with
+    /// the stream, whether or not it is at a boundary.
+    pub fn split<'a>(&'a mut self, data: &'a [u8]) -> Chunks<'a> {
+        self.reset();
+        Chunks {
+            chunker: self,
+            rest: data,
+        }
+    }
+}
+
+/// An iterator over the chunks of a stream, created by [`Chunker::split`].
+#[derive(Debug)]
+pub struct Chunks<'a> {
+    chunker: &'a mut Chunker,
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let len = match self.chunker.find_boundary(self.rest) {
+            Some(len) => len,
+            None => {
+                self.chunker.reset();
+                self.rest.len()
+            }
+        };
+        let (chunk, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Some(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crc::{Crc, CRC_64_XZ};
+
+    const CRC: Crc<u64> = Crc::<u64>::new(&CRC_64_XZ);
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len as u32)
+            .map(|i| (i.wrapping_mul(0x9e37_79b9) >> 24) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn test_rolling() {
+        let data = data(1000);
+        for window in [1, 2, 15, 16, 17, 64, 300] {
+            let mut rolling = RollingCrc64::new(window);
+            assert_eq!(rolling.sum64(), CRC.checksum(&vec![0; window]));
+            for (i, &b) in data.iter().enumerate() {
+                let out = if i >= window { data[i - window] } else { 0 };
+                rolling.roll(out, b);
+                let start = (i + 1).saturating_sub(window);
+                let mut expected = vec![0; window - (i + 1 - start)];
+                expected.extend_from_slice(&data[start..=i]);
+                assert_eq!(rolling.sum64(), CRC.checksum(&expected), "{}+{}", window, i);
+            }
+            rolling.fill(&data[..window]);
+            assert_eq!(rolling.sum64(), CRC.checksum(&data[..window]));
+        }
+    }
+
+    #[test]
+    fn test_chunker() {
+        let data = data(200_000);
+        let mut chunker = Chunker::new(32, 0x3ff);
+        let chunks: Vec<&[u8]> = chunker.split(&data).collect();
+        assert_eq!(chunks.concat(), data);
+        assert!(chunks.len() > 50, "{}", chunks.len());
+        for chunk in &chunks[..chunks.len() - 1] {
+            assert!(chunk.len() >= 32);
+            assert_eq!(CRC.checksum(&chunk[chunk.len() - 32..]) & 0x3ff, 0);
+        }
+
+        // feeding the stream in pieces finds the same boundaries.
+        let mut chunker = Chunker::new(32, 0x3ff);
+        let mut lens = vec![];
+        let mut len = 0;
+        for mut piece in data.chunks(1000) {
+            while let Some(n) = chunker.find_boundary(piece) {
+                lens.push(len + n);
+                len = 0;
+                piece = &piece[n..];
+            }
+            len += piece.len();
+        }
+        lens.push(len);
+        assert_eq!(lens, chunks.iter().map(|c| c.len()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_chunker_resync() {
+        // an insertion only changes the chunks around it.
+        let data = data(100_000);
+        let mut edited = data.clone();
+        edited.splice(50_000..50_000, b"inserted".iter().copied());
+
+        let mut chunker = Chunker::new(16, 0xff);
+        let a: Vec<Vec<u8>> = chunker.split(&data).map(<[u8]>::to_vec).collect();
+        let b: Vec<Vec<u8>> = chunker.split(&edited).map(<[u8]>::to_vec).collect();
+        let common = a.iter().filter(|c| b.contains(c)).count();
+        assert!(common + 3 >= a.len(), "{} of {}", common, a.len());
+    }
+
+    #[test]
+    fn test_max_size() {
+        let mut chunker = Chunker::new(8, !0).with_max_size(100);
+        let lens: Vec<usize> = chunker.split(&[7; 250]).map(|c| c.len()).collect();
+        assert_eq!(lens, [100, 100, 50]);
+    }
+}
@@ -0,0 +1,377 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+//! Runtime verification of the detected implementation.
+//!
+//! A CPU with broken microcode, an emulator with a buggy carryless
+//! multiplication, or a miscompiled build would otherwise silently produce
+//! wrong checksums. [`self_test`](crate::self_test) checks every entry point of the
+//! implementation chosen by [`Digest::new`](crate::Digest::new) against known
+//! CRC values, then against the table-based implementation on pseudo-random
+//! data.
+
+use super::table::{self, Params};
+use super::{
+    detected_backend, pclmulqdq, Backend, CopyUpdateFn, SelfTestError, UpdateChunksFn, UpdateFn,
+    UpdateManyFn, UpdateMultiFn, UpdateWithFn,
+};
+
+/// Messages with their known CRC-64-ECMA values.
+pub(crate) static STANDARD_VECTORS: &[(&[u8], u64)] = &[
+    (b"", 0),
+    (b"@", 0x7b1b_8ab9_8fa4_b8f8),
+    (b"1\x97", 0xfeb8_f7a1_ae3b_9bd4),
+    (b"M\"\xdf", 0xc016_0ce8_dd46_74d3),
+    (b"l\xcd\x13\xd7", 0x5c60_a6af_8299_6ea8),
+
+    (&[0; 32], 0xc95a_f861_7cd5_330c),
+    (&[255; 32], 0xe95d_ce9e_faa0_9acf),
+    (b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0A\x0B\x0C\x0D\x0E\x0F\x10\x11\x12\x13\x14\x15\x16\x17\x18\x19\x1A\x1B\x1C\x1D\x1E\x1F", 0x7fe5_71a5_8708_4d10),
+
+    (&[0; 1024], 0xc378_6397_2069_270c),
+];
+
+/// Messages with their known CRC-64/NVME values, the 32-byte ones being
+/// those of the NVM Command Set specification.
+pub(crate) static NVME_VECTORS: &[(&[u8], u64)] = &[
+    (b"", 0),
+    (b"@", 0x0280_8afa_9582_aa47),
+    (b"123456789", 0xae8b_1486_0a79_9888),
+
+    (&[0; 32], 0xcf34_7343_4d4e_cf3b),
+    (&[255; 32], 0xa0a0_6974_c34d_63c4),
+    (b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0A\x0B\x0C\x0D\x0E\x0F\x10\x11\x12\x13\x14\x15\x16\x17\x18\x19\x1A\x1B\x1C\x1D\x1E\x1F", 0xb9d9_d4a8_492c_bd7f),
+
+    (&[0; 1024], 0x691b_b2b0_9be5_498a),
+];
+
+/// Message lengths cross-checked against the table-based implementation,
+/// around the block sizes of the SIMD implementations.
+const LENGTHS: &[usize] = &[
+    0, 1, 7, 8, 15, 16, 17, 63, 64, 65, 127, 128, 129, 255, 256, 257, 511, 512, 513, 1000, 4096,
+];
+
+/// The size of the chunks the messages are split into for `update_chunks`,
+/// so that the SIMD blocks straddle them.
+const CHUNK_SIZE: usize = 100;
+
+/// Checks the implementation used by [`Digest::new`](crate::Digest::new),
+/// see [`self_test`](crate::self_test).
+pub(crate) fn run() -> Result<Backend, SelfTestError> {
+    let backend = detected_backend();
+    check(backend, &Functions::detected())?;
+    Ok(backend)
+}
+
+/// The entry points of an implementation.
+struct Functions {
+    update: UpdateFn,
+    update_chunks: UpdateChunksFn,
+    copy_update: CopyUpdateFn,
+    update_many: UpdateManyFn,
+    update_with: UpdateWithFn,
+    update_multi: UpdateMultiFn<2>,
+}
+
+impl Functions {
+    fn detected() -> Self {
+        Self {
+            update: pclmulqdq::get_update(),
+            update_chunks: pclmulqdq::get_update_chunks(),
+            copy_update: pclmulqdq::get_copy_update(),
+            update_many: pclmulqdq::get_update_many(),
+            update_with: pclmulqdq::get_update_with(),
+            update_multi: pclmulqdq::get_update_multi(),
+        }
+    }
+
+    #[cfg(test)]
+    fn table() -> Self {
+        Self {
+            update: table::update,
+            update_chunks: table::update_chunks,
+            copy_update: table::copy_update,
+            update_many: table::update_many,
+            update_with: table::update_with,
+            update_multi: table::update_multi,
+        }
+    }
+
+    /// Updates the CRC state of `params` with `bytes` through `function`.
+    /// Returns `None` if `copy_update` copied `bytes` wrongly.
+    fn run(
+        &self,
+        function: Function,
+        params: &'static Params,
+        state: u64,
+        bytes: &[u8],
+    ) -> Option<u64> {
+        match function {
+            Function::Update => Some((self.update)(state, bytes)),
+            Function::UpdateChunks => {
+                Some((self.update_chunks)(state, &mut bytes.chunks(CHUNK_SIZE)))
+            }
+            Function::CopyUpdate => {
+                let mut dst = vec![0; bytes.len()];
+                let state = (self.copy_update)(state, &mut dst, bytes);
+                (dst == bytes).then_some(state)
+            }
+            Function::UpdateMany => {
+                let mut states = [state];
+                (self.update_many)(&mut states, &[bytes]);
+                Some(states[0])
+            }
+            Function::UpdateWith => Some((self.update_with)(params, state, bytes)),
+            Function::UpdateMulti => {
+                // Runs the other polynomial alongside, from another state.
+                let other = if std::ptr::eq(params, &table::ECMA) {
+                    &table::NVME
+                } else {
+                    &table::ECMA
+                };
+                let mut states = [state, !state];
+                (self.update_multi)(&mut states, &[params, other], bytes);
+                Some(states[0])
+            }
+        }
+    }
+}
+
+/// A field of [`Functions`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Function {
+    Update,
+    UpdateChunks,
+    CopyUpdate,
+    UpdateMany,
+    UpdateWith,
+    UpdateMulti,
+}
+
+impl Function {
+    /// The name reported in [`SelfTestError`].
+    fn name(self) -> &'static str {
+        match self {
+            Self::Update => "update",
+            Self::UpdateChunks => "update_chunks",
+            Self::CopyUpdate => "copy_update",
+            Self::UpdateMany => "update_many",
+            Self::UpdateWith => "update_with",
+            Self::UpdateMulti => "update_multi",
+        }
+    }
+}
+
+/// The functions checked, with the polynomial they are checked with.
+const FUNCTIONS: &[(Function, &Params)] = &[
+    (Function::Update, &table::ECMA),
+    (Function::UpdateChunks, &table::ECMA),
+    (Function::CopyUpdate, &table::ECMA),
+    (Function::UpdateMany, &table::ECMA),
+    (Function::UpdateWith, &table::ECMA),
+    (Function::UpdateWith, &table::NVME),
+    (Function::UpdateMulti, &table::ECMA),
+    (Function::UpdateMulti, &table::NVME),
+];
+
+fn check(backend: Backend, functions: &Functions) -> Result<(), SelfTestError> {
+    for &(function, params) in FUNCTIONS {
+        let vectors = if std::ptr::eq(params, &table::ECMA) {
+            STANDARD_VECTORS
+        } else {
+            NVME_VECTORS
+        };
+        for (input, expected) in vectors {
+            let Some(actual) = functions.run(function, params, !0, input) else {
+                return Err(SelfTestError::Copy {
+                    backend,
+                    offset: 0,
+                    len: input.len(),
+                });
+            };
+            if !actual != *expected {
+                return Err(SelfTestError::KnownVector {
+                    backend,
+                    function: function.name(),
+                    len: input.len(),
+                    expected: *expected,
+                    actual: !actual,
+                });
+            }
+        }
+    }
+
+    // xorshift64, so that every run checks the same data.
+    let mut seed = 0x9e37_79b9_7f4a_7c15_u64;
+    let mut next = || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    };
+    let mut buf = vec![0; 4096 + 32];
+    for chunk in buf.chunks_mut(8) {
+        chunk.copy_from_slice(&next().to_le_bytes());
+    }
+    let base = buf.as_ptr().align_offset(16);
+
+    for offset in 0..16 {
+        let inputs: Vec<&[u8]> = LENGTHS
+            .iter()
+            .map(|&len| &buf[base + offset..][..len])
+            .collect();
+        let states: Vec<u64> = inputs.iter().map(|_| next()).collect();
+        let mismatch = |function, input: &[u8], expected, actual| SelfTestError::Mismatch {
+            backend,
+            function,
+            offset,
+            len: input.len(),
+            expected,
+            actual,
+        };
+
+        for &(function, params) in FUNCTIONS {
+            for (input, &state) in inputs.iter().zip(&states) {
+                let expected = table::update_with(params, state, input);
+                let Some(actual) = functions.run(function, params, state, input) else {
+                    return Err(SelfTestError::Copy {
+                        backend,
+                        offset,
+                        len: input.len(),
+                    });
+                };
+                if actual != expected {
+                    return Err(mismatch(function.name(), input, expected, actual));
+                }
+            }
+        }
+
+        // All the messages in one call, as `checksum_many` makes it.
+        let mut actual = states.clone();
+        (functions.update_many)(&mut actual, &inputs);
+        for ((input, state), actual) in inputs.iter().zip(states).zip(actual) {
+            let expected = table::update(state, input);
+            if actual != expected {
+                return Err(mismatch("update_many", input, expected, actual));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_test() {
+        assert_eq!(run(), Ok(detected_backend()));
+        assert_eq!(check(Backend::Table, &Functions::table()), Ok(()));
+    }
+
+    #[test]
+    fn test_broken_backend() {
+        fn broken(state: u64, bytes: &[u8]) -> u64 {
+            let state = table::update(state, bytes);
+            if bytes.len() == 513 {
+                state ^ 1
+            } else {
+                state
+            }
+        }
+        let functions = Functions {
+            update: broken,
+            ..Functions::table()
+        };
+        let err = check(Backend::FakeSimd, &functions).unwrap_err();
+        assert!(matches!(
+            err,
+            SelfTestError::Mismatch {
+                backend: Backend::FakeSimd,
+                function: "update",
+                offset: 0,
+                len: 513,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_broken_functions() {
+        fn broken_many(states: &mut [u64], messages: &[&[u8]]) {
+            table::update_many(states, messages);
+            if messages.len() > 1 {
+                states[3] ^= 1;
+            }
+        }
+        let functions = Functions {
+            update_many: broken_many,
+            ..Functions::table()
+        };
+        assert!(matches!(
+            check(Backend::FakeSimd, &functions),
+            Err(SelfTestError::Mismatch {
+                function: "update_many",
+                offset: 0,
+                len: 8,
+                ..
+            })
+        ));
+
+        fn broken_multi(states: &mut [u64; 2], params: &[&'static Params; 2], bytes: &[u8]) {
+            table::update_multi(states, params, bytes);
+            if std::ptr::eq(params[0], &table::NVME) && bytes.len() == 17 {
+                states[0] ^= 1;
+            }
+        }
+        let functions = Functions {
+            update_multi: broken_multi,
+            ..Functions::table()
+        };
+        assert!(matches!(
+            check(Backend::FakeSimd, &functions),
+            Err(SelfTestError::Mismatch {
+                function: "update_multi",
+                offset: 0,
+                len: 17,
+                ..
+            })
+        ));
+
+        fn broken_with(params: &Params, state: u64, bytes: &[u8]) -> u64 {
+            let state = table::update_with(params, state, bytes);
+            if std::ptr::eq(params, &table::NVME) && bytes.len() == 32 {
+                state ^ 1
+            } else {
+                state
+            }
+        }
+        let functions = Functions {
+            update_with: broken_with,
+            ..Functions::table()
+        };
+        assert!(matches!(
+            check(Backend::FakeSimd, &functions),
+            Err(SelfTestError::KnownVector {
+                function: "update_with",
+                len: 32,
+                ..
+            })
+        ));
+
+        fn broken_copy(state: u64, dst: &mut [u8], src: &[u8]) -> u64 {
+            let state = table::copy_update(state, dst, src);
+            if let Some(last) = dst.get_mut(64) {
+                *last ^= 1;
+            }
+            state
+        }
+        let functions = Functions {
+            copy_update: broken_copy,
+            ..Functions::table()
+        };
+        assert!(matches!(
+            check(Backend::FakeSimd, &functions),
+            Err(SelfTestError::Copy { len: 1024, .. })
+        ));
+    }
+}
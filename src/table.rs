@@ -7,6 +7,9 @@
 
 #![allow(clippy::unreadable_literal)]
 
+use super::gf2::{reflect, Poly64};
+use std::borrow::Borrow;
+
 pub(crate) fn update(mut state: u64, bytes: &[u8]) -> u64 {
     // Safe.
     let (left, middle, right) = unsafe { bytes.align_to::<[u8; 16]>() };
@@ -22,13 +25,71 @@ pub(crate) fn update(mut state: u64, bytes: &[u8]) -> u64 {
     state
 }
 
+pub(crate) fn update_many(states: &mut [u64], messages: &[&[u8]]) {
+    for (state, bytes) in states.iter_mut().zip(messages) {
+        *state = update(*state, bytes);
+    }
+}
+
+pub(crate) fn copy_update(state: u64, dst: &mut [u8], src: &[u8]) -> u64 {
+    dst.copy_from_slice(src);
+    update(state, src)
+}
+
+pub(crate) fn update_chunks(state: u64, chunks: &mut dyn Iterator<Item = &[u8]>) -> u64 {
+    chunks.fold(state, update)
+}
+
+/// Performs the CRC-64 update for the polynomial described by `params`.
+///
+/// Same as `update`, but reading the tables through `params`.
+pub(crate) fn update_with(params: &Params, state: u64, bytes: &[u8]) -> u64 {
+    update_tables(&params.tables, state, bytes)
+}
+
+/// Performs the CRC-64 update with the tables built by `build_tables`.
+pub(crate) fn update_tables<T: Borrow<[u64; 256]>>(
+    tables: &[T; 16],
+    mut state: u64,
+    bytes: &[u8],
+) -> u64 {
+    let t = |i: usize, b: u8| tables[i].borrow()[usize::from(b)];
+    let (left, middle, right) = unsafe { bytes.align_to::<[u8; 16]>() };
+    for b in left {
+        state = (state >> 8) ^ t(0, b ^ (state as u8));
+    }
+    for b in middle {
+        let s = state.to_le_bytes();
+        state = (0..8).fold(0, |acc, i| {
+            acc ^ t(i, b[15 - i]) ^ t(i + 8, b[7 - i] ^ s[7 - i])
+        });
+    }
+    for b in right {
+        state = (state >> 8) ^ t(0, b ^ (state as u8));
+    }
+    state
+}
+
+pub(crate) fn update_multi<const N: usize>(
+    states: &mut [u64; N],
+    params: &[&'static Params; N],
+    bytes: &[u8],
+) {
+    for (state, params) in states.iter_mut().zip(params) {
+        *state = update_with(params, *state, bytes);
+    }
+}
+
 /// Performs the CRC-64-ECMA update, one byte at a time.
-fn update_1(state: u64, b: u8) -> u64 {
+pub(crate) fn update_1(state: u64, b: u8) -> u64 {
     (state >> 8) ^ TABLE_0[usize::from(b ^ (state as u8))]
 }
 
 /// Performs the CRC-64-ECMA update, 16 bytes at a time.
 fn update_16(state: u64, b: &[u8; 16]) -> u64 {
+    // The CRC is reflected, so the first byte of the input lines up with the
+    // least significant byte of the state. `to_le_bytes` puts that byte at
+    // `s[0]` regardless of the host byte order.
     let s = state.to_le_bytes();
     TABLE_0[usize::from(b[15])]
         ^ TABLE_1[usize::from(b[14])]
@@ -66,6 +127,11 @@ pub(crate) const K_895: u64 = 0x9478_74de_5950_52cb;
 pub(crate) const K_959: u64 = 0x9e73_5cb5_9b47_24da;
 pub(crate) const K_1023: u64 = 0xd7d8_6b2a_f73d_e740;
 pub(crate) const K_1087: u64 = 0x8757_d71d_4fcc_1000;
+// Only used by the 256-byte folding of the AArch64 EOR3 implementation.
+#[cfg(any(test, all(not(feature = "fake-simd"), target_arch = "aarch64")))]
+pub(crate) const K_2047: u64 = 0xf31f_d927_1e22_8b79;
+#[cfg(any(test, all(not(feature = "fake-simd"), target_arch = "aarch64")))]
+pub(crate) const K_2111: u64 = 0x8260_adf2_381a_d81c;
 
 /// The reciprocal of the ECMA polynomial.
 pub(crate) const POLY: u64 = 0x92d8_af2b_af0e_1e85;
@@ -73,6 +139,141 @@ pub(crate) const POLY: u64 = 0x92d8_af2b_af0e_1e85;
 /// The polynomial quotient x¹²⁸/POLY.
 pub(crate) const MU: u64 = 0x9c3e_466c_1729_63d5;
 
+/// The lookup tables and SIMD folding constants of a reflected CRC-64.
+///
+/// The `k_nnn` constants are computed by bit_reverse(x^nnn mod P), like the
+/// `K_nnn` constants above, and `poly` and `mu` match `POLY` and `MU`.
+pub(crate) struct Params {
+    pub(crate) tables: [&'static [u64; 256]; 16],
+    pub(crate) k_127: u64,
+    pub(crate) k_191: u64,
+    pub(crate) k_255: u64,
+    pub(crate) k_319: u64,
+    pub(crate) k_383: u64,
+    pub(crate) k_447: u64,
+    pub(crate) k_511: u64,
+    pub(crate) k_575: u64,
+    pub(crate) k_639: u64,
+    pub(crate) k_703: u64,
+    pub(crate) k_767: u64,
+    pub(crate) k_831: u64,
+    pub(crate) k_895: u64,
+    pub(crate) k_959: u64,
+    pub(crate) k_1023: u64,
+    pub(crate) k_1087: u64,
+    pub(crate) poly: u64,
+    pub(crate) mu: u64,
+}
+
+impl Params {
+    /// Computes the constants of `poly` (in normal notation, omitting the
+    /// x⁶⁴ term), given the tables built by `build_tables(poly)`.
+    const fn new(poly: u64, tables: &'static [[u64; 256]; 16]) -> Self {
+        let p = Poly64::new(poly);
+        let mut refs = [&tables[0]; 16];
+        let mut i = 1;
+        while i < 16 {
+            refs[i] = &tables[i];
+            i += 1;
+        }
+        Self {
+            tables: refs,
+            k_127: k(&p, 127),
+            k_191: k(&p, 191),
+            k_255: k(&p, 255),
+            k_319: k(&p, 319),
+            k_383: k(&p, 383),
+            k_447: k(&p, 447),
+            k_511: k(&p, 511),
+            k_575: k(&p, 575),
+            k_639: k(&p, 639),
+            k_703: k(&p, 703),
+            k_767: k(&p, 767),
+            k_831: k(&p, 831),
+            k_895: k(&p, 895),
+            k_959: k(&p, 959),
+            k_1023: k(&p, 1023),
+            k_1087: k(&p, 1087),
+            poly: reflect(poly) << 1 | 1,
+            mu: reflect(p.mu()) << 1 | 1,
+        }
+    }
+}
+
+/// Computes bit_reverse(x^n mod P).
+const fn k(p: &Poly64, n: u32) -> u64 {
+    let mut res = 1;
+    let mut i = 0;
+    while i < n {
+        res = p.mul_x(res);
+        i += 1;
+    }
+    reflect(res)
+}
+
+/// Builds the 16 lookup tables of `poly` (in normal notation, omitting the
+/// x⁶⁴ term), i.e. the equivalent of `TABLE_0` to `TABLE_15`.
+pub(crate) const fn build_tables(poly: u64) -> [[u64; 256]; 16] {
+    let reflected = reflect(poly);
+    let mut tables = [[0; 256]; 16];
+    let mut i = 0;
+    while i < 256 {
+        let mut value = i as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            value = value >> 1 ^ if value & 1 != 0 { reflected } else { 0 };
+            bit += 1;
+        }
+        tables[0][i] = value;
+        i += 1;
+    }
+    let mut t = 1;
+    while t < 16 {
+        let mut i = 0;
+        while i < 256 {
+            let prev = tables[t - 1][i];
+            tables[t][i] = prev >> 8 ^ tables[0][(prev & 0xff) as usize];
+            i += 1;
+        }
+        t += 1;
+    }
+    tables
+}
+
+/// The parameters of CRC-64/XZ, using the ECMA polynomial.
+pub(crate) static ECMA: Params = Params {
+    tables: [
+        &TABLE_0, &TABLE_1, &TABLE_2, &TABLE_3, &TABLE_4, &TABLE_5, &TABLE_6, &TABLE_7, &TABLE_8,
+        &TABLE_9, &TABLE_10, &TABLE_11, &TABLE_12, &TABLE_13, &TABLE_14, &TABLE_15,
+    ],
+    k_127: K_127,
+    k_191: K_191,
+    k_255: K_255,
+    k_319: K_319,
+    k_383: K_383,
+    k_447: K_447,
+    k_511: K_511,
+    k_575: K_575,
+    k_639: K_639,
+    k_703: K_703,
+    k_767: K_767,
+    k_831: K_831,
+    k_895: K_895,
+    k_959: K_959,
+    k_1023: K_1023,
+    k_1087: K_1087,
+    poly: POLY,
+    mu: MU,
+};
+
+/// The NVME polynomial in normal notation, omitting the x⁶⁴ term.
+const NVME_POLY: u64 = 0xad93_d235_94c9_3659;
+
+static NVME_TABLES: [[u64; 256]; 16] = build_tables(NVME_POLY);
+
+/// The parameters of CRC-64/NVME.
+pub(crate) static NVME: Params = Params::new(NVME_POLY, &NVME_TABLES);
+
 // CRC table for the ECMA polynomial (`0x42F0E1EBA9EA3693`).
 // Generated by running `./build_table 0`.
 //
@@ -4251,3 +4452,66 @@ static TABLE_15: [u64; 256] = [
     0x61f25e662328b26e,
     0x81af8af1e911888a,
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static ECMA_TABLES: [[u64; 256]; 16] = build_tables(0x42f0_e1eb_a9ea_3693);
+
+    #[test]
+    fn test_build_tables() {
+        for (built, table) in ECMA_TABLES.iter().zip(ECMA.tables) {
+            assert_eq!(built, table);
+        }
+    }
+
+    #[test]
+    fn test_params() {
+        let params = Params::new(0x42f0_e1eb_a9ea_3693, &ECMA_TABLES);
+        for (a, b) in [
+            (params.k_127, K_127),
+            (params.k_191, K_191),
+            (params.k_255, K_255),
+            (params.k_319, K_319),
+            (params.k_383, K_383),
+            (params.k_447, K_447),
+            (params.k_511, K_511),
+            (params.k_575, K_575),
+            (params.k_639, K_639),
+            (params.k_703, K_703),
+            (params.k_767, K_767),
+            (params.k_831, K_831),
+            (params.k_895, K_895),
+            (params.k_959, K_959),
+            (params.k_1023, K_1023),
+            (params.k_1087, K_1087),
+            (params.poly, POLY),
+            (params.mu, MU),
+        ] {
+            assert_eq!(a, b);
+        }
+    }
+
+    #[test]
+    fn test_k() {
+        let p = Poly64::new(0x42f0_e1eb_a9ea_3693);
+        assert_eq!(k(&p, 1023), K_1023);
+        assert_eq!(k(&p, 1087), K_1087);
+        assert_eq!(k(&p, 2047), K_2047);
+        assert_eq!(k(&p, 2111), K_2111);
+    }
+
+    #[test]
+    fn test_update_with() {
+        let data: Vec<u8> = (0..300u32).map(|i| (i * 7) as u8).collect();
+        for len in 0..data.len() {
+            assert_eq!(
+                update_with(&ECMA, !0, &data[..len]),
+                update(!0, &data[..len])
+            );
+        }
+        // CRC-64/NVME check value.
+        assert_eq!(!update_with(&NVME, !0, b"123456789"), 0xae8b_1486_0a79_9888);
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+//! Incrementally maintained CRC-64 of a mutable message.
+//!
+//! [`CrcTree`] splits the message into fixed-size blocks, and keeps the CRCs
+//! of the blocks at the leaves of a binary tree. Each interior node holds
+//! the combined CRC of its subtree (see the [`combine`](crate::combine)
+//! module), so rewriting a block only recomputes the nodes on the path from
+//! its leaf to the root.
+
+use super::combine::combine;
+use super::Digest;
+
+/// The CRC-64-ECMA value of a range of blocks, with its length.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+struct Node {
+    crc: u64,
+    len: u64,
+}
+
+impl Node {
+    fn combine(self, right: Self) -> Self {
+        Self {
+            crc: combine(self.crc, right.crc, right.len),
+            len: self.len + right.len,
+        }
+    }
+}
+
+/// The CRC-64-ECMA value of a message made of fixed-size blocks, kept up to
+/// date as the blocks are rewritten.
+///
+/// Building the tree reads the whole message once. Afterwards, rewriting a
+/// block takes O(`block_size`) time to checksum it, plus O(log `blocks`)
+/// combinations to update the value of the message.
+///
+/// ```
+/// use crc64fast::tree::CrcTree;
+///
+/// let mut tree = CrcTree::new(b"123456xyz", 3);
+/// tree.update_block(2, b"789");
+/// assert_eq!(tree.sum64(), 0x995d_c9bb_df19_39fa);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CrcTree {
+    block_size: usize,
+    blocks: usize,
+    /// The nodes in breadth-first order, from the root at index 1. The
+    /// children of node `i` are nodes `2 * i` and `2 * i + 1`, and the
+    /// leaves are the last `nodes.len() / 2` nodes, padded with empty ones.
+    nodes: Vec<Node>,
+}
+
+impl CrcTree {
+    /// Builds the tree of `data`, split into blocks of `block_size` bytes.
+    /// The last block may be shorter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is 0.
+    pub fn new(data: &[u8], block_size: usize) -> Self {
+        assert!(block_size > 0, "block size must not be zero");
+        let blocks = (data.len() + block_size - 1) / block_size;
+        let leaves = blocks.next_power_of_two();
+        let mut nodes = vec![Node::default(); 2 * leaves];
+        for (leaf, block) in nodes[leaves..].iter_mut().zip(data.chunks(block_size)) {
+            *leaf = leaf_of(block);
+        }
+        for i in (1..leaves).rev() {
+            nodes[i] = nodes[2 * i].combine(nodes[2 * i + 1]);
+        }
+        Self {
+            block_size,
+            blocks,
+            nodes,
+        }
+    }
+
+    /// Returns the size of the blocks.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Returns the number of blocks.
+    pub fn blocks(&self) -> usize {
+        self.blocks
+    }
+
+    /// Returns the length of the message.
+    pub fn len(&self) -> u64 {
+        self.nodes[1].len
+    }
+
+    /// Returns whether the message is empty.
+    pub fn is_empty(&self) -> bool {
+        self.blocks == 0
+    }
+
+    /// Returns the CRC-64-ECMA value of block `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn block_crc(&self, index: usize) -> u64 {
+        assert!(index < self.blocks, "block index out of bounds");
+        self.nodes[self.nodes.len() / 2 + index].crc
+    }
+
+    /// Replaces the content of block `index` with `block`, and updates the
+    /// value of the message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds, or if `block` is not as long as
+    /// the block it replaces.
+    pub fn update_block(&mut self, index: usize, block: &[u8]) {
+        assert!(index < self.blocks, "block index out of bounds");
+        let mut i = self.nodes.len() / 2 + index;
+        assert_eq!(
+            block.len() as u64,
+            self.nodes[i].len,
+            "block length mismatch"
+        );
+        self.nodes[i] = leaf_of(block);
+        while i > 1 {
+            i /= 2;
+            self.nodes[i] = self.nodes[2 * i].combine(self.nodes[2 * i + 1]);
+        }
+    }
+
+    /// Returns the CRC-64-ECMA value of the message.
+    pub fn sum64(&self) -> u64 {
+        self.nodes[1].crc
+    }
+}
+
+fn leaf_of(block: &[u8]) -> Node {
+    let mut digest = Digest::new();
+    digest.write(block);
+    Node {
+        crc: digest.sum64(),
+        len: block.len() as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crc::{Crc, CRC_64_XZ};
+    use proptest::prelude::*;
+
+    const CRC: Crc<u64> = Crc::<u64>::new(&CRC_64_XZ);
+
+    #[test]
+    fn test_empty() {
+        let tree = CrcTree::new(&[], 512);
+        assert!(tree.is_empty());
+        assert_eq!(tree.blocks(), 0);
+        assert_eq!(tree.len(), 0);
+        assert_eq!(tree.sum64(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "block length mismatch")]
+    fn test_length_mismatch() {
+        let mut tree = CrcTree::new(&[0; 1000], 512);
+        tree.update_block(1, &[0; 512]);
+    }
+
+    proptest! {
+        #[test]
+        fn updates(
+            mut data in proptest::collection::vec(any::<u8>(), 0..8192),
+            block_size in 1..1024usize,
+            updates in proptest::collection::vec(any::<(prop::sample::Index, u8)>(), 0..8),
+        ) {
+            let mut tree = CrcTree::new(&data, block_size);
+            prop_assert_eq!(tree.blocks(), data.chunks(block_size).count());
+            prop_assert_eq!(tree.len(), data.len() as u64);
+            prop_assert_eq!(tree.sum64(), CRC.checksum(&data));
+            if data.is_empty() {
+                return Ok(());
+            }
+            for (index, fill) in updates {
+                let index = index.index(tree.blocks());
+                let block = data.chunks_mut(block_size).nth(index).unwrap();
+                block.fill(fill);
+                tree.update_block(index, block);
+                prop_assert_eq!(tree.block_crc(index), CRC.checksum(block));
+                prop_assert_eq!(tree.sum64(), CRC.checksum(&data));
+            }
+        }
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright 2026 TiKV Project Authors. Licensed under MIT or Apache-2.0.
+
+use std::process::{Command, Output};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_crc64fast-tool"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_invalid_bytes() {
+    for message in ["é0", "0é", "12g4", "123"] {
+        let output = run(&["reveng", &format!("{}:0x0", message)]);
+        assert_eq!(output.status.code(), Some(1), "{}: {:?}", message, output);
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains(message), "{}: {}", message, stderr);
+        assert!(!stderr.contains("panicked"), "{}: {}", message, stderr);
+    }
+}
+
+#[test]
+fn test_hex_prefix() {
+    for poly in ["0x1b", "0X1b", "1b"] {
+        let output = run(&["hd", poly, "normal", "64"]);
+        assert_eq!(output.status.code(), Some(0), "{}: {:?}", poly, output);
+    }
+}